@@ -1,4 +1,5 @@
 use advent_of_code::parse_from_lines;
+
 use itertools::Itertools;
 
 advent_of_code::solution!(1);
@@ -38,6 +39,41 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(similarity)
 }
 
+// Compute the similarity score of two sorted lists by merging them with two pointers,
+// as an alternative to counting frequencies in a HashMap
+#[cfg(test)]
+fn similarity_sorted(a: &[u32], b: &[u32]) -> u32 {
+    use std::cmp::Ordering;
+
+    let mut similarity = 0;
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                let val = a[i];
+
+                // Count how many times this value appears in the right list
+                let start = j;
+                while j < b.len() && b[j] == val {
+                    j += 1;
+                }
+                let count = (j - start) as u32;
+
+                // Every occurrence in the left list gets the same score
+                while i < a.len() && a[i] == val {
+                    similarity += val * count;
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    similarity
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,4 +89,16 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(31));
     }
+
+    #[test]
+    fn test_similarity_sorted() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (mut list_1, mut list_2) = parse_lists(&input);
+
+        list_1.sort();
+        list_2.sort();
+
+        assert_eq!(Some(similarity_sorted(&list_1, &list_2)), part_two(&input));
+        assert_eq!(similarity_sorted(&list_1, &list_2), 31);
+    }
 }
@@ -32,26 +32,54 @@ fn is_gradually_decreasing(window: &[u32; 2]) -> bool {
 
 pub fn part_two(input: &str) -> Option<u32> {
     let safe_reports = parse_from_lines::<u32>(input)
-        .map(|report| is_safe_with_dampener(report.collect()))
+        .map(|report| is_safe_with_budget(&report.collect::<Vec<_>>(), 1))
         .filter(|b| *b)
         .count();
 
     Some(safe_reports as u32)
 }
 
-// Compute if a report is safe if we remove at most 1 element
-fn is_safe_with_dampener(report: Vec<u32>) -> bool {
+// Compute if a report is safe if we remove at most `removals` elements
+fn is_safe_with_budget(report: &[u32], removals: usize) -> bool {
+    if is_safe(report.to_vec()) {
+        return true;
+    }
+
+    if removals == 0 {
+        return false;
+    }
+
+    // Try removing each element, then spend the rest of the budget on the remaining report
+    (0..report.len()).any(|i| {
+        let mut modified_report = report.to_vec();
+        modified_report.remove(i);
+
+        is_safe_with_budget(&modified_report, removals - 1)
+    })
+}
+
+// Find which level the dampener should remove to make the report safe
+// Returns Ok(None) if the report is already safe without removing anything,
+// or an error if removing any single level does not make it safe
+#[cfg(test)]
+fn safe_dampener_index(report: &[u32]) -> Result<Option<usize>, String> {
+    if is_safe(report.to_vec()) {
+        return Ok(None);
+    }
+
     // Try removing each element
     for i in 0..report.len() {
-        let mut modified_report = report.clone();
+        let mut modified_report = report.to_vec();
         modified_report.remove(i);
 
         if is_safe(modified_report) {
-            return true;
+            return Ok(Some(i));
         }
     }
 
-    false
+    Err(format!(
+        "Report {report:?} is unsafe regardless of which level is removed"
+    ))
 }
 
 #[cfg(test)]
@@ -69,4 +97,42 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(4));
     }
+
+    #[test]
+    fn test_is_safe_with_budget() {
+        // Both 7s need to be removed
+        let report = [1, 2, 7, 7, 3, 4];
+        assert!(!is_safe_with_budget(&report, 0));
+        assert!(!is_safe_with_budget(&report, 1));
+        assert!(is_safe_with_budget(&report, 2));
+        assert!(is_safe_with_budget(&report, 3));
+
+        // Agrees with the single-removal dampener on the example
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let safe_reports = parse_from_lines::<u32>(&input)
+            .map(|report| report.collect::<Vec<_>>())
+            .filter(|report| is_safe_with_budget(report, 1))
+            .count();
+        assert_eq!(safe_reports, 4);
+        for report in parse_from_lines::<u32>(&input) {
+            let report: Vec<u32> = report.collect();
+            assert_eq!(
+                is_safe_with_budget(&report, 1),
+                safe_dampener_index(&report).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_safe_dampener_index() {
+        // Examples from the problem, safe only after removing one level
+        assert_eq!(safe_dampener_index(&[1, 3, 2, 4, 5]), Ok(Some(1)));
+        assert_eq!(safe_dampener_index(&[8, 6, 4, 4, 1]), Ok(Some(2)));
+
+        // Already safe
+        assert_eq!(safe_dampener_index(&[7, 6, 4, 2, 1]), Ok(None));
+
+        // Can't be fixed
+        assert!(safe_dampener_index(&[1, 2, 7, 8, 9]).is_err());
+    }
 }
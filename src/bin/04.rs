@@ -5,30 +5,7 @@ advent_of_code::solution!(4);
 pub fn part_one(input: &str) -> Option<u32> {
     let word_search = WordSearch::new(input);
 
-    let xs = word_search.get_locations_of('X');
-
-    let mut count = 0;
-    for (r, c) in xs.iter() {
-        let r = *r as i32;
-        let c = *c as i32;
-
-        for (dr, dc) in [
-            (0, 1),
-            (0, -1),
-            (1, 0),
-            (-1, 0),
-            (1, 1),
-            (1, -1),
-            (-1, 1),
-            (-1, -1),
-        ] {
-            if word_search.is_xmas(r, c, dr, dc) {
-                count += 1;
-            }
-        }
-    }
-
-    Some(count)
+    Some(word_search.xmas_matches().len() as u32)
 }
 
 struct WordSearch {
@@ -70,6 +47,29 @@ impl WordSearch {
         set
     }
 
+    // Return the starting cell and direction of each XMAS match
+    fn xmas_matches(&self) -> Vec<(usize, usize, (i32, i32))> {
+        let mut matches = Vec::new();
+        for (r, c) in self.get_locations_of('X') {
+            for (dr, dc) in [
+                (0, 1),
+                (0, -1),
+                (1, 0),
+                (-1, 0),
+                (1, 1),
+                (1, -1),
+                (-1, 1),
+                (-1, -1),
+            ] {
+                if self.is_xmas(r as i32, c as i32, dr, dc) {
+                    matches.push((r, c, (dr, dc)));
+                }
+            }
+        }
+
+        matches
+    }
+
     fn is_xmas(&self, r: i32, c: i32, dr: i32, dc: i32) -> bool {
         // Assume we are starting from an 'X' already so skip the check
         self.get_char_at(r + dr, c + dc) == Some('M')
@@ -116,6 +116,22 @@ mod tests {
         assert_eq!(result, Some(18));
     }
 
+    #[test]
+    fn test_xmas_matches() {
+        let input = "\
+XMAS
+MM..
+A.A.
+S..S";
+        let word_search = WordSearch::new(input);
+
+        let matches: HashSet<_> = word_search.xmas_matches().into_iter().collect();
+        assert_eq!(
+            matches,
+            HashSet::from([(0, 0, (0, 1)), (0, 0, (1, 0)), (0, 0, (1, 1))])
+        );
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
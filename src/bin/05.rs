@@ -5,22 +5,34 @@ use itertools::Itertools;
 advent_of_code::solution!(5);
 
 // Store rules as a map of each page to the set of pages that are required to be before it.
+// The rules and updates are separated by one or more blank lines. Lines are trimmed so
+// that Windows line endings (\r\n) and stray whitespace are handled.
 fn parse_rules_and_updates(input: &str) -> (HashMap<u32, HashSet<u32>>, Vec<Vec<u32>>) {
     let mut rules: HashMap<u32, HashSet<u32>> = HashMap::new();
     let mut updates = Vec::new();
-    let mut end_of_rules = false;
-    for line in input.lines() {
+
+    let mut lines = input
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty());
+
+    let mut found_separator = false;
+    for line in lines.by_ref() {
         if line.is_empty() {
-            end_of_rules = true;
-            continue;
+            found_separator = true;
+            break;
         }
 
-        if !end_of_rules {
-            let (a, b) = parse_rule(line);
-            rules.entry(b).or_default().insert(a);
-        } else {
-            updates.push(parse_update(line));
-        }
+        let (a, b) = parse_rule(line);
+        rules.entry(b).or_default().insert(a);
+    }
+    assert!(
+        found_separator,
+        "Did not find a blank line separating the rules from the updates"
+    );
+
+    for line in lines.filter(|line| !line.is_empty()) {
+        updates.push(parse_update(line));
     }
 
     (rules, updates)
@@ -42,6 +54,16 @@ fn parse_update(update: &str) -> Vec<u32> {
         .collect()
 }
 
+// Return the middle page, or None if the update has an even number of pages
+// since there is no single middle page
+fn middle_page(update: &[u32]) -> Option<u32> {
+    if update.len().is_multiple_of(2) {
+        return None;
+    }
+
+    Some(update[update.len() / 2])
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
     let (rules, updates) = parse_rules_and_updates(input);
 
@@ -66,7 +88,8 @@ pub fn part_one(input: &str) -> Option<u32> {
 
         if update_ok {
             // add middle page
-            sum += update[update.len() / 2];
+            sum += middle_page(&update)
+                .unwrap_or_else(|| panic!("Update {update:?} has no middle page"));
         }
     }
 
@@ -100,7 +123,8 @@ pub fn part_two(input: &str) -> Option<u32> {
         }
 
         if reordered {
-            sum += reordered_page[update.len() / 2];
+            sum += middle_page(&reordered_page)
+                .unwrap_or_else(|| panic!("Update {update:?} has no middle page"));
         }
     }
 
@@ -117,6 +141,35 @@ mod tests {
         assert_eq!(result, Some(143));
     }
 
+    #[test]
+    fn test_parse_rules_and_updates() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let expected = parse_rules_and_updates(&input);
+        assert_eq!(expected.0.len(), 6);
+        assert_eq!(expected.1.len(), 6);
+
+        // Windows line endings
+        let crlf_input = input.replace('\n', "\r\n");
+        assert_eq!(parse_rules_and_updates(&crlf_input), expected);
+        assert_eq!(part_one(&crlf_input), Some(143));
+
+        // Multiple blank lines
+        let input = input.replacen("\n\n", "\n\n\n\n", 1);
+        assert_eq!(parse_rules_and_updates(&input), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Did not find a blank line")]
+    fn test_parse_rules_and_updates_no_separator() {
+        parse_rules_and_updates("47|53\n97|13");
+    }
+
+    #[test]
+    fn test_middle_page() {
+        assert_eq!(middle_page(&[75, 47, 61, 53, 29]), Some(61));
+        assert_eq!(middle_page(&[97, 13, 75, 29]), None);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,7 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use advent_of_code::{Coord, Dimensions, Direction};
-use itertools::Itertools;
 
 advent_of_code::solution!(6);
 
@@ -14,11 +13,16 @@ struct Map {
 }
 
 impl Map {
-    fn new(dimensions: Dimensions, start: Coord, obstructions: HashSet<Coord>) -> Self {
+    fn new(
+        dimensions: Dimensions,
+        start: Coord,
+        start_dir: Direction,
+        obstructions: HashSet<Coord>,
+    ) -> Self {
         Self {
             dimensions,
             curr_pos: start,
-            curr_dir: Direction::Up,
+            curr_dir: start_dir,
             obstructions,
         }
     }
@@ -51,67 +55,101 @@ impl Map {
     }
 }
 
-fn parse(input: &str) -> Map {
-    let mut start = None;
+// Return a map for each guard, since guards are simulated independently
+fn parse(input: &str) -> Vec<Map> {
+    let mut guards = Vec::new();
     let mut obstructions = HashSet::new();
 
     let dimensions = Dimensions::from_input(input);
 
     // Reverse the lines since our coordinate system has (0, 0) in the bottom left
     for (y, line) in input.lines().rev().enumerate() {
-        if let Some(x) = line.chars().position(|c| c == '^') {
-            start = Some(Coord::new(x as i64, y as i64));
+        for (x, c) in line.chars().enumerate() {
+            let coord = Coord::new(x as i64, y as i64);
+            match c {
+                '^' => guards.push((coord, Direction::Up)),
+                'v' => guards.push((coord, Direction::Down)),
+                '<' => guards.push((coord, Direction::Left)),
+                '>' => guards.push((coord, Direction::Right)),
+                '#' => {
+                    obstructions.insert(coord);
+                }
+                _ => {}
+            }
         }
+    }
+
+    assert!(!guards.is_empty(), "Did not find any guard characters ^v<>");
+    guards
+        .into_iter()
+        .map(|(start, start_dir)| Map::new(dimensions, start, start_dir, obstructions.clone()))
+        .collect()
+}
+
+// Count how many times the guards occupy each cell during their patrols
+// Note: turning counts as occupying the same cell again
+fn visit_counts(input: &str) -> HashMap<Coord, u32> {
+    let mut visit_counts = HashMap::new();
+
+    for mut map in parse(input) {
+        *visit_counts.entry(map.curr_pos).or_default() += 1; // include the starting position
 
-        for x in line.chars().positions(|c| c == '#') {
-            obstructions.insert(Coord::new(x as i64, y as i64));
+        while let Some(coord) = map.step() {
+            *visit_counts.entry(coord).or_default() += 1;
         }
     }
 
-    let start = start.expect("Did not find start character ^");
-    Map::new(dimensions, start, obstructions)
+    visit_counts
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut map = parse(input);
-
-    let mut visited = HashSet::new();
-    visited.insert(map.curr_pos); // include the starting position
+// Total number of steps the guards take until they exit the map, including the final
+// step off the map
+// Note: turning counts as a step, so this is more than the number of distinct cells visited
+#[cfg(test)]
+fn total_steps(input: &str) -> u32 {
+    let mut total_steps = 0;
 
-    while let Some(coord) = map.step() {
-        visited.insert(coord);
+    for mut map in parse(input) {
+        total_steps += 1; // the step off the map
+        while map.step().is_some() {
+            total_steps += 1;
+        }
     }
 
-    Some(visited.len() as u32)
+    total_steps
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let map = parse(input);
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(visit_counts(input).len() as u32)
+}
 
-    let mut loop_positions = 0;
+pub fn part_two(input: &str) -> Option<u32> {
+    // Only check positions that were visited in part 1, as these can affect the path
+    let visited = visit_counts(input).into_keys().collect();
 
-    let new_obstructions_to_check = {
-        let mut map = map.clone();
-        let mut visited = HashSet::new();
-        visited.insert(map.curr_pos); // include the starting position
+    loop_obstructions_in(input, &visited)
+}
 
-        while let Some(coord) = map.step() {
-            visited.insert(coord);
-        }
+// Count the cells in region where adding an obstruction would cause the guard to loop
+// Returns None if there isn't exactly one guard, since it's unclear what a loop means otherwise
+fn loop_obstructions_in(input: &str, region: &HashSet<Coord>) -> Option<u32> {
+    let mut guards = parse(input);
+    if guards.len() != 1 {
+        return None;
+    }
+    let map = guards.remove(0);
 
-        visited
-    };
+    let mut loop_positions = 0;
 
     // Brute force - try all the new positions for an obstruction
-    // Only check positions that were visited in part 1, as these can affect the path
-    for coord in new_obstructions_to_check {
-        if map.obstructions.contains(&coord) {
-            // Already an obstruction here so we can't add one
+    for coord in region {
+        if map.obstructions.contains(coord) || !map.dimensions.in_bounds(coord) {
+            // Already an obstruction here (or off the map) so we can't add one
             continue;
         }
 
         let mut map = map.clone();
-        map.add_obstruction(coord);
+        map.add_obstruction(*coord);
 
         // Visited states (pos and dir)
         let mut visited = HashSet::new();
@@ -142,6 +180,78 @@ mod tests {
         assert_eq!(result, Some(41));
     }
 
+    #[test]
+    fn test_visit_counts() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let map = &parse(&input)[0];
+        let visit_counts = visit_counts(&input);
+
+        // The guard starts here and crosses it again later on
+        assert_eq!(visit_counts.get(&map.curr_pos), Some(&2));
+        assert_eq!(visit_counts.len(), 41);
+    }
+
+    #[test]
+    fn test_multiple_guards() {
+        let input = "\
+..#...
+.....#
+.^....
+....<.
+#.....";
+        let guard_1 = input.replace('<', ".");
+        let guard_2 = input.replace('^', ".");
+
+        let visited: HashSet<Coord> = visit_counts(input).into_keys().collect();
+        let visited_1: HashSet<Coord> = visit_counts(&guard_1).into_keys().collect();
+        let visited_2: HashSet<Coord> = visit_counts(&guard_2).into_keys().collect();
+
+        assert_eq!(visited, visited_1.union(&visited_2).copied().collect());
+        assert_eq!(part_one(input), Some(visited.len() as u32));
+
+        // Loops aren't defined with more than one guard
+        assert_eq!(part_two(input), None);
+        assert!(part_two(&guard_1).is_some());
+    }
+
+    #[test]
+    fn test_loop_obstructions_in() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let visited: HashSet<Coord> = visit_counts(&input).into_keys().collect();
+        let full_count = loop_obstructions_in(&input, &visited);
+        assert_eq!(full_count, Some(6));
+
+        // Only the top half of the map
+        let top_half = visited
+            .iter()
+            .filter(|coord| coord.y >= 5)
+            .copied()
+            .collect();
+        assert!(loop_obstructions_in(&input, &top_half) <= full_count);
+
+        // Obstruction right next to the guard's starting position, from the example
+        let region = HashSet::from([Coord::new(3, 3)]);
+        assert_eq!(loop_obstructions_in(&input, &region), Some(1));
+
+        assert_eq!(loop_obstructions_in(&input, &HashSet::new()), Some(0));
+    }
+
+    #[test]
+    fn test_total_steps() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let steps = total_steps(&input);
+
+        // 44 moves to another cell (some cells are visited twice), 10 turns and the step off the map
+        assert!(steps > 41);
+        assert_eq!(steps, 55);
+
+        // Single step off the map
+        assert_eq!(total_steps("^"), 1);
+
+        // Turn, then walk 2 cells and off the map
+        assert_eq!(total_steps("#..\n^.."), 4);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
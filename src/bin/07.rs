@@ -1,3 +1,6 @@
+#[cfg(test)]
+use std::collections::HashMap;
+
 use itertools::Itertools;
 
 advent_of_code::solution!(7);
@@ -8,6 +11,9 @@ pub struct UnfinishedCalibrationEquation {
 }
 
 impl UnfinishedCalibrationEquation {
+    // Original recursive implementation, which allocates new operands for each recursive call.
+    // Kept to cross-check could_be_true_streaming in tests.
+    #[cfg(test)]
     fn could_be_true(&self, operators: &[fn(u64, u64) -> u64]) -> bool {
         // Base cases
         if self.operands.is_empty() {
@@ -42,6 +48,85 @@ impl UnfinishedCalibrationEquation {
     }
 }
 
+// Check whether the operands could produce the result by folding them from left to right,
+// carrying the result so far instead of allocating new operands for each recursive call
+fn could_be_true_streaming(
+    result: u64,
+    operands: &[u64],
+    operators: &[fn(u64, u64) -> u64],
+) -> bool {
+    match operands.split_first() {
+        None => false,
+        Some((&first, rest)) => could_be_true_from(result, first, rest, operators),
+    }
+}
+
+fn could_be_true_from(
+    result: u64,
+    so_far: u64,
+    operands: &[u64],
+    operators: &[fn(u64, u64) -> u64],
+) -> bool {
+    let Some((&next, rest)) = operands.split_first() else {
+        // Base case: all operands have been used
+        return so_far == result;
+    };
+
+    operators
+        .iter()
+        .any(|operator| could_be_true_from(result, operator(so_far, next), rest, operators))
+}
+
+// Same as could_be_true_streaming, but caches whether each (result so far, index of the
+// next operand) can still reach the result. Different operator choices often produce the
+// same partial result, e.g. 1 + 1 and 1 * 2, so long operand lists can skip a lot of work.
+#[cfg(test)]
+fn could_be_true_memoized(
+    result: u64,
+    operands: &[u64],
+    operators: &[fn(u64, u64) -> u64],
+) -> bool {
+    match operands.split_first() {
+        None => false,
+        Some((&first, _)) => {
+            could_be_true_memoized_from(result, first, 1, operands, operators, &mut HashMap::new())
+        }
+    }
+}
+
+#[cfg(test)]
+fn could_be_true_memoized_from(
+    result: u64,
+    so_far: u64,
+    next_idx: usize,
+    operands: &[u64],
+    operators: &[fn(u64, u64) -> u64],
+    memo: &mut HashMap<(u64, usize), bool>,
+) -> bool {
+    let Some(&next) = operands.get(next_idx) else {
+        // Base case: all operands have been used
+        return so_far == result;
+    };
+
+    if let Some(&could_be_true) = memo.get(&(so_far, next_idx)) {
+        return could_be_true;
+    }
+
+    let could_be_true = operators.iter().any(|operator| {
+        could_be_true_memoized_from(
+            result,
+            operator(so_far, next),
+            next_idx + 1,
+            operands,
+            operators,
+            memo,
+        )
+    });
+    memo.insert((so_far, next_idx), could_be_true);
+
+    could_be_true
+}
+
 // Define operators
 
 fn add(x: u64, y: u64) -> u64 {
@@ -84,7 +169,7 @@ pub fn part_one(input: &str) -> Option<u64> {
 
     let operators = [add, mult];
     for equation in parse(input) {
-        if equation.could_be_true(&operators) {
+        if could_be_true_streaming(equation.result, &equation.operands, &operators) {
             total_calibration_result += equation.result;
         }
     }
@@ -97,7 +182,7 @@ pub fn part_two(input: &str) -> Option<u64> {
 
     let operators = [add, mult, concat];
     for equation in parse(input) {
-        if equation.could_be_true(&operators) {
+        if could_be_true_streaming(equation.result, &equation.operands, &operators) {
             total_calibration_result += equation.result;
         }
     }
@@ -120,4 +205,58 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(11387));
     }
+
+    #[test]
+    fn test_could_be_true_streaming() {
+        let operators: [fn(u64, u64) -> u64; 3] = [add, mult, concat];
+
+        for equation in parse(&advent_of_code::template::read_file("examples", DAY)) {
+            for num_operators in 2..=3 {
+                assert_eq!(
+                    could_be_true_streaming(
+                        equation.result,
+                        &equation.operands,
+                        &operators[..num_operators]
+                    ),
+                    equation.could_be_true(&operators[..num_operators])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_could_be_true_memoized() {
+        let operators: [fn(u64, u64) -> u64; 3] = [add, mult, concat];
+
+        for equation in parse(&advent_of_code::template::read_file("examples", DAY)) {
+            for num_operators in 2..=3 {
+                assert_eq!(
+                    could_be_true_memoized(
+                        equation.result,
+                        &equation.operands,
+                        &operators[..num_operators]
+                    ),
+                    could_be_true_streaming(
+                        equation.result,
+                        &equation.operands,
+                        &operators[..num_operators]
+                    )
+                );
+            }
+        }
+
+        // Long equation where many operator choices give the same partial results
+        let operands = [1, 2, 1, 1, 2, 1, 1, 2, 1, 1, 2, 1];
+        for result in [0, 12, 15, 18, 64, 1211, 121121121121, 999] {
+            for num_operators in 2..=3 {
+                assert_eq!(
+                    could_be_true_memoized(result, &operands, &operators[..num_operators]),
+                    could_be_true_streaming(result, &operands, &operators[..num_operators]),
+                    "Mismatch for result {result} with {num_operators} operators"
+                );
+            }
+        }
+        assert!(could_be_true_memoized(16, &operands, &operators[..2]));
+        assert!(could_be_true_memoized(121121121121, &operands, &operators));
+    }
 }
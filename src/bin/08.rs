@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use advent_of_code::{Coord, Dimensions};
+use advent_of_code::{reduce_vector, Coord, Dimensions};
 
 advent_of_code::solution!(8);
 
@@ -14,6 +14,41 @@ impl Map {
     fn check_in_bounds(&self, coord: &Coord) -> bool {
         self.dimensions.in_bounds(coord)
     }
+
+    // Number of antennas of each frequency
+    #[cfg(test)]
+    fn frequency_counts(&self) -> HashMap<char, usize> {
+        self.antennas
+            .iter()
+            .map(|(frequency, antennas)| (*frequency, antennas.len()))
+            .collect()
+    }
+}
+
+// Which pairs of antennas create antinodes, based on their displacement
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum AntinodeMode {
+    // All pairs, as in the puzzle
+    #[default]
+    All,
+    // Only pairs in the same row or column
+    #[cfg(test)]
+    Cardinal,
+    // Only pairs on the same 45 degree diagonal
+    #[cfg(test)]
+    Diagonal,
+}
+
+impl AntinodeMode {
+    fn allows(&self, delta: &Coord) -> bool {
+        match (self, delta) {
+            (Self::All, _) => true,
+            #[cfg(test)]
+            (Self::Cardinal, delta) => delta.x == 0 || delta.y == 0,
+            #[cfg(test)]
+            (Self::Diagonal, delta) => delta.x.abs() == delta.y.abs(),
+        }
+    }
 }
 
 fn parse(input: &str) -> Map {
@@ -40,7 +75,13 @@ fn parse(input: &str) -> Map {
     }
 }
 
+// Antinodes only arise from pairs of antennas with the same frequency, so frequencies
+// with a single antenna contribute nothing, and a map without antennas has 0 antinodes.
 pub fn part_one(input: &str) -> Option<u32> {
+    Some(antinodes(input, AntinodeMode::default()).len() as u32)
+}
+
+fn antinodes(input: &str, mode: AntinodeMode) -> HashSet<Coord> {
     let map = parse(input);
     let mut antinodes = HashSet::new();
 
@@ -52,16 +93,19 @@ pub fn part_one(input: &str) -> Option<u32> {
                 let antenna_1 = antennas[i];
                 let antenna_2 = antennas[j];
 
-                let (dx, dy) = antenna_2.diff(&antenna_1);
+                let delta = antenna_2 - antenna_1;
+                if !mode.allows(&delta) {
+                    continue;
+                }
 
-                // apply (dx, dy) on either side of 1 and 2 to find potential antinodes
+                // apply delta on either side of 1 and 2 to find potential antinodes
 
-                let antinode_1 = antenna_1.step(-dx, -dy);
+                let antinode_1 = antenna_1 - delta;
                 if map.check_in_bounds(&antinode_1) {
                     antinodes.insert(antinode_1);
                 }
 
-                let antinode_2 = antenna_2.step(dx, dy);
+                let antinode_2 = antenna_2 + delta;
                 if map.check_in_bounds(&antinode_2) {
                     antinodes.insert(antinode_2);
                 }
@@ -69,16 +113,19 @@ pub fn part_one(input: &str) -> Option<u32> {
         }
     }
 
-    Some(antinodes.len() as u32)
+    antinodes
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
+    Some(resonant_antinodes(input, AntinodeMode::default()).len() as u32)
+}
+
+// Antinodes taking resonant harmonics into account, i.e. at any multiple of the distance
+fn resonant_antinodes(input: &str, mode: AntinodeMode) -> HashSet<Coord> {
     let map = parse(input);
     let mut antinodes = HashSet::new();
 
     for (_frequency, antennas) in map.antennas.clone() {
-        // Every antenna is an antinode
-        antinodes.extend(&antennas);
         // For every pair of antennas, they create a line of potential antinodes.
         // Keep applying the diff until we are outside the grid
         for i in 0..antennas.len() {
@@ -86,27 +133,33 @@ pub fn part_two(input: &str) -> Option<u32> {
                 let antenna_1 = antennas[i];
                 let antenna_2 = antennas[j];
 
-                let (dx, dy) = antenna_2.diff(&antenna_1);
+                let delta = antenna_2 - antenna_1;
+                if !mode.allows(&delta) {
+                    continue;
+                }
 
-                // apply (dx, dy) on either side of 1 and 2 until we are
-                // outside the grid to find potential antinodes
+                // Step by the smallest vector along the line, so that any grid points
+                // between the antennas are covered too. This includes both antennas,
+                // since they are in line with each other.
+                let (dx, dy) = reduce_vector(delta.x, delta.y);
 
-                let mut antinode_1 = antenna_1.step(-dx, -dy);
-                while map.check_in_bounds(&antinode_1) {
-                    antinodes.insert(antinode_1);
-                    antinode_1 = antinode_1.step(-dx, -dy);
+                // walk from antenna 1 in both directions until we are outside the grid
+                let mut antinode = antenna_1;
+                while map.check_in_bounds(&antinode) {
+                    antinodes.insert(antinode);
+                    antinode = antinode - (dx, dy);
                 }
 
-                let mut antinode_2 = antenna_2.step(dx, dy);
-                while map.check_in_bounds(&antinode_2) {
-                    antinodes.insert(antinode_2);
-                    antinode_2 = antinode_2.step(dx, dy);
+                let mut antinode = antenna_1 + (dx, dy);
+                while map.check_in_bounds(&antinode) {
+                    antinodes.insert(antinode);
+                    antinode = antinode + (dx, dy);
                 }
             }
         }
     }
 
-    Some(antinodes.len() as u32)
+    antinodes
 }
 
 #[cfg(test)]
@@ -124,4 +177,53 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(34));
     }
+
+    #[test]
+    fn test_antinode_modes() {
+        // a antennas are in the same column, b antennas are diagonal
+        let input = "\
+.......
+.......
+.a...b.
+....b..
+.a.....
+.......
+.......";
+
+        let cardinal = HashSet::from([Coord::new(1, 0), Coord::new(1, 6)]);
+        let diagonal = HashSet::from([Coord::new(3, 2), Coord::new(6, 5)]);
+
+        assert_eq!(antinodes(input, AntinodeMode::Cardinal), cardinal);
+        assert_eq!(antinodes(input, AntinodeMode::Diagonal), diagonal);
+        assert_eq!(
+            antinodes(input, AntinodeMode::All),
+            cardinal.union(&diagonal).copied().collect()
+        );
+
+        // With resonant harmonics, every antenna in an allowed pair is also an antinode
+        let resonant = resonant_antinodes(input, AntinodeMode::Diagonal);
+        assert!(resonant.contains(&Coord::new(4, 3)));
+        assert!(!resonant.contains(&Coord::new(1, 2)));
+        assert_eq!(resonant.len(), 6);
+    }
+
+    #[test]
+    fn test_resonant_antinodes_between_antennas() {
+        // The antennas are 2 apart, so the grid point between them is in line too
+        let input = ".a.a.";
+        assert_eq!(resonant_antinodes(input, AntinodeMode::All).len(), 5);
+    }
+
+    #[test]
+    fn test_single_antenna() {
+        let input = "....\n.a..\n....\n";
+        let map = parse(input);
+
+        assert_eq!(map.frequency_counts(), HashMap::from([('a', 1)]));
+        assert_eq!(part_one(input), Some(0));
+
+        let empty = "....\n....\n";
+        assert!(parse(empty).frequency_counts().is_empty());
+        assert_eq!(part_one(empty), Some(0));
+    }
 }
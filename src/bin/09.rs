@@ -77,28 +77,28 @@ pub fn part_one(input: &str) -> Option<u64> {
     Some(checksum)
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct File {
     id: usize,
     size: u8,   // blocks
     idx: usize, // block idx of the first file block
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct FreeSpace {
     size: u8, // blocks
     #[allow(dead_code)]
     idx: usize, // block idx of first free block
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum FileOrFreeSpace {
     File(File),
     FreeSpace(FreeSpace),
 }
 
 // { starting block idx : file or free space }
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 struct Filesystem(BTreeMap<usize, FileOrFreeSpace>);
 
 impl Filesystem {
@@ -106,6 +106,52 @@ impl Filesystem {
         Filesystem(map)
     }
 
+    // Build a filesystem from an array of blocks, where each block holds the ID of the
+    // file occupying it or None if it is free
+    // Panics if any file or free space is longer than 255 blocks
+    // Note: empty free space between two files can't be represented in the blocks,
+    // so it will not be included
+    #[cfg(test)]
+    fn from_blocks(blocks: &[Option<usize>]) -> Self {
+        let mut map = BTreeMap::new();
+
+        for (id, chunk) in &blocks.iter().enumerate().chunk_by(|(_, block)| **block) {
+            let mut chunk = chunk.peekable();
+            let idx = chunk.peek().unwrap().0;
+            let count = chunk.count();
+            let size = u8::try_from(count).unwrap_or_else(|_| {
+                panic!("Run of {count} blocks starting at {idx} is too long, the max is 255")
+            });
+
+            let file_or_free_space = match id {
+                Some(id) => FileOrFreeSpace::File(File { id, size, idx }),
+                None => FileOrFreeSpace::FreeSpace(FreeSpace { size, idx }),
+            };
+            map.insert(idx, file_or_free_space);
+        }
+
+        Filesystem::new(map)
+    }
+
+    // Expand the filesystem into an array of blocks, where each block holds the ID of the
+    // file occupying it or None if it is free
+    #[cfg(test)]
+    fn to_blocks(&self) -> Vec<Option<usize>> {
+        let mut blocks = Vec::new();
+        for (_, file_or_free_space) in self.0.iter() {
+            match file_or_free_space {
+                FileOrFreeSpace::File(file) => {
+                    blocks.extend(vec![Some(file.id); file.size as usize]);
+                }
+                FileOrFreeSpace::FreeSpace(free_space) => {
+                    blocks.extend(vec![None; free_space.size as usize]);
+                }
+            }
+        }
+
+        blocks
+    }
+
     fn calculate_checksum(&self) -> u64 {
         let mut checksum = 0;
         for (idx, file_or_free_space) in self.0.iter() {
@@ -175,8 +221,9 @@ impl Filesystem {
         }
     }
 
+    // Render the blocks the same way as the puzzle description, e.g. 00...111...2
     #[cfg(test)]
-    fn print_blocks(&self) {
+    fn render_blocks(&self) -> String {
         let mut s = "".to_string();
         for (_, file_or_free_space) in self.0.iter() {
             match file_or_free_space {
@@ -189,7 +236,28 @@ impl Filesystem {
             }
         }
 
-        println!("{s}");
+        s
+    }
+
+    #[cfg(test)]
+    fn print_blocks(&self) {
+        println!("{}", self.render_blocks());
+    }
+
+    // Iterate over each file starting from the right and try to move it to the leftmost
+    // free space block, calling after_each_file once each file has been processed
+    fn compact(&mut self, mut after_each_file: impl FnMut(&Filesystem)) {
+        let mut files_by_id = BTreeMap::new();
+        for (_, file_or_free_space) in self.0.iter() {
+            if let FileOrFreeSpace::File(file) = file_or_free_space {
+                files_by_id.insert(file.id, *file);
+            }
+        }
+
+        for (_, file) in files_by_id.into_iter().rev() {
+            self.fill_leftmost_free_space(&file);
+            after_each_file(self);
+        }
     }
 }
 
@@ -237,20 +305,10 @@ fn parse_part_two(input: &str) -> Filesystem {
 pub fn part_two(input: &str) -> Option<u64> {
     let mut filesystem = parse_part_two(input);
 
-    let mut files_by_id = BTreeMap::new();
-    for (_, file_or_free_space) in filesystem.0.clone() {
-        if let FileOrFreeSpace::File(file) = file_or_free_space {
-            files_by_id.insert(file.id, file);
-        }
-    }
-
-    // Iterate over each file starting from the right and try to move it to the leftmost free space block
-    for (_, file) in files_by_id.into_iter().rev() {
-        filesystem.fill_leftmost_free_space(&file);
-
+    filesystem.compact(|_filesystem| {
         #[cfg(test)]
-        filesystem.print_blocks();
-    }
+        _filesystem.print_blocks();
+    });
 
     // calculate the checksum
     let checksum = filesystem.calculate_checksum();
@@ -258,6 +316,70 @@ pub fn part_two(input: &str) -> Option<u64> {
     Some(checksum)
 }
 
+// The rendered block layout after each file is processed during part two compaction
+#[cfg(test)]
+fn compaction_steps(input: &str) -> Vec<String> {
+    let mut filesystem = parse_part_two(input);
+
+    let mut steps = Vec::new();
+    filesystem.compact(|filesystem| steps.push(filesystem.render_blocks()));
+
+    steps
+}
+
+// Simulate whole-file moves directly on an array of blocks, where each block
+// holds the ID of the file occupying it or None if it is free.
+// Much slower than part_two but simple enough to cross-check it.
+#[cfg(test)]
+fn part_two_naive(input: &str) -> u64 {
+    let mut blocks: Vec<Option<usize>> = Vec::new();
+    for (i, c) in input.trim().chars().enumerate() {
+        let size = (c as u8 - b'0') as usize;
+        let block = if i % 2 == 0 { Some(i / 2) } else { None };
+        blocks.extend(vec![block; size]);
+    }
+
+    let max_file_id = blocks.iter().flatten().max().copied().unwrap_or(0);
+
+    for file_id in (0..=max_file_id).rev() {
+        let Some(file_start) = blocks.iter().position(|b| *b == Some(file_id)) else {
+            continue;
+        };
+        let file_size = blocks[file_start..]
+            .iter()
+            .take_while(|b| **b == Some(file_id))
+            .count();
+
+        // Find the leftmost run of free blocks that fits the file
+        let mut run_start = 0;
+        let mut run_len = 0;
+        for i in 0..file_start {
+            if blocks[i].is_some() {
+                run_len = 0;
+                continue;
+            }
+
+            if run_len == 0 {
+                run_start = i;
+            }
+            run_len += 1;
+
+            if run_len == file_size {
+                // Move the file
+                blocks[run_start..run_start + file_size].fill(Some(file_id));
+                blocks[file_start..file_start + file_size].fill(None);
+                break;
+            }
+        }
+    }
+
+    blocks
+        .into_iter()
+        .enumerate()
+        .map(|(i, block)| block.map_or(0, |file_id| (i * file_id) as u64))
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,7 +394,12 @@ mod tests {
         // 02211122..2....
         // 022111222......
         let result = part_one(input);
-        let expected = 1 * 2 + 2 * 2 + 3 * 1 + 4 * 1 + 5 * 1 + 6 * 2 + 7 * 2 + 8 * 2;
+        // Sum of position * file id over the final layout
+        let expected = "022111222"
+            .chars()
+            .enumerate()
+            .map(|(position, id)| position as u64 * u64::from(id.to_digit(10).unwrap()))
+            .sum();
         assert_eq!(result, Some(expected));
     }
 
@@ -287,4 +414,85 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2858));
     }
+
+    #[test]
+    fn test_filesystem_blocks_round_trip() {
+        let filesystem = parse_part_two("12345");
+        let blocks = filesystem.to_blocks();
+
+        // 0..111....22222
+        assert_eq!(blocks.len(), 15);
+        assert_eq!(blocks[..3], [Some(0), None, None]);
+        assert_eq!(blocks[10..], [Some(2); 5]);
+
+        assert_eq!(Filesystem::from_blocks(&blocks), filesystem);
+    }
+
+    #[test]
+    fn test_compaction_steps() {
+        let steps = compaction_steps(&advent_of_code::template::read_file("examples", DAY));
+
+        // One step per file, starting with the file with the highest ID
+        assert_eq!(steps.len(), 10);
+        assert_eq!(steps[0], "0099.111...2...333.44.5555.6666.777.8888..");
+        assert_eq!(
+            steps.last().unwrap(),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+    }
+
+    #[test]
+    fn test_filesystem_blocks_round_trip_long_runs() {
+        // Runs of 10 or more blocks, which can't come from a single digit of a disk map
+        let mut blocks = vec![Some(0); 12];
+        blocks.extend(vec![None; 30]);
+        blocks.extend(vec![Some(1); 3]);
+        blocks.extend(vec![None; 1]);
+        blocks.extend(vec![Some(2); 255]);
+
+        let filesystem = Filesystem::from_blocks(&blocks);
+        assert_eq!(filesystem.0.len(), 5);
+        assert_eq!(filesystem.to_blocks(), blocks);
+
+        // Also after compacting
+        let mut filesystem = parse_part_two("2333133121414131402");
+        filesystem.compact(|_| {});
+        assert_eq!(
+            Filesystem::from_blocks(&filesystem.to_blocks()).to_blocks(),
+            filesystem.to_blocks()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Run of 300 blocks starting at 1 is too long")]
+    fn test_filesystem_from_blocks_too_long() {
+        let mut blocks = vec![Some(0)];
+        blocks.extend(vec![None; 300]);
+        Filesystem::from_blocks(&blocks);
+    }
+
+    #[test]
+    fn test_part_two_naive() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(Some(part_two_naive(&input)), part_two(&input));
+
+        // Pseudo-random disk map, using a simple linear congruential generator
+        let mut state: u64 = 12345;
+        let mut random_digit = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (state >> 33) % 10
+        };
+        let input: String = (0..199)
+            .map(|i| {
+                // Files must contain at least one block
+                let digit = if i % 2 == 0 {
+                    random_digit() % 9 + 1
+                } else {
+                    random_digit()
+                };
+                char::from(b'0' + digit as u8)
+            })
+            .collect();
+        assert_eq!(Some(part_two_naive(&input)), part_two(&input));
+    }
 }
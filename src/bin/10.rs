@@ -1,19 +1,17 @@
 use std::collections::HashSet;
 
-use advent_of_code::{Dimensions, Grid};
+use advent_of_code::{Direction, Grid};
 
 advent_of_code::solution!(10);
 
 fn parse(input: &str) -> Grid<u8> {
-    let dimensions = Dimensions::from_input(input);
-
-    let values = input
-        .lines()
-        .rev()
-        .map(|line| line.chars().map(|c| (c as u8) - 48).collect())
-        .collect();
+    Grid::from_input(input, |c| c as u8 - b'0')
+}
 
-    Grid { dimensions, values }
+// Number of trailheads (positions with height 0) in the map
+#[cfg(test)]
+fn trailhead_count(input: &str) -> usize {
+    parse(input).positions_of(&0).len()
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -31,8 +29,8 @@ pub fn part_one(input: &str) -> Option<u32> {
             // Calculate all reachable nodes with value i
             let mut new_pending = HashSet::new();
             for position in pending {
-                for (dx, dy) in [(0, 1), (0, -1), (1, 0), (-1, 0)] {
-                    let coord = position.step(dx, dy);
+                for dir in Direction::all() {
+                    let coord = position.step_in_direction(dir);
                     if grid.get(&coord) == Some(i) {
                         new_pending.insert(coord);
                     }
@@ -65,8 +63,8 @@ pub fn part_two(input: &str) -> Option<u32> {
             // Calculate all reachable trails with length i
             let mut new_pending = Vec::new();
             for position in pending {
-                for (dx, dy) in [(0, 1), (0, -1), (1, 0), (-1, 0)] {
-                    let coord = position.step(dx, dy);
+                for dir in Direction::all() {
+                    let coord = position.step_in_direction(dir);
                     if grid.get(&coord) == Some(i) {
                         new_pending.push(coord);
                     }
@@ -98,4 +96,15 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(81));
     }
+
+    #[test]
+    fn test_no_trailheads() {
+        let input = "123\n456\n789";
+        assert_eq!(trailhead_count(input), 0);
+        assert_eq!(part_one(input), Some(0));
+        assert_eq!(part_two(input), Some(0));
+
+        let example = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(trailhead_count(&example), 9);
+    }
 }
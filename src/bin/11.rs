@@ -37,7 +37,8 @@ pub fn part_one(input: &str) -> Option<u32> {
             } else {
                 // If none of the other rules apply, the stone is replaced by a new stone;
                 // the old stone's number multiplied by 2024 is engraved on the new stone.
-                new_nums.push(i * 2024);
+                // Give up if the number gets too big to engrave.
+                new_nums.push(i.checked_mul(2024)?);
             }
         }
 
@@ -50,18 +51,22 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u64> {
     let nums = parse_from_lines(input).next().unwrap();
 
-    let sum = nums.map(|i| num_stones_after_n_blinks(i, 75)).sum::<u64>();
+    let sum = nums
+        .map(|i| num_stones_after_n_blinks(i, 75))
+        .sum::<Result<u64, String>>()
+        .unwrap_or_else(|e| panic!("{e}"));
 
-    Some(sum as u64)
+    Some(sum)
 }
 
 /// Recursive and memoized computation of the number of stones resulting
 /// from a single starting stone value and a number of blinks.
-#[cached]
-fn num_stones_after_n_blinks(initial: u64, blinks: usize) -> u64 {
+/// Returns an error if a stone's number overflows when multiplied by 2024.
+#[cached(result = true)]
+fn num_stones_after_n_blinks(initial: u64, blinks: usize) -> Result<u64, String> {
     if blinks == 0 {
         // Base case -> just the one stone
-        return 1;
+        return Ok(1);
     }
 
     if initial == 0 {
@@ -71,7 +76,7 @@ fn num_stones_after_n_blinks(initial: u64, blinks: usize) -> u64 {
     }
 
     let num_digits = initial.to_string().len();
-    if num_digits % 2 == 0 {
+    if num_digits.is_multiple_of(2) {
         // If the stone is engraved with a number that has an even number of digits,
         // it is replaced by two stones.
         // The left half of the digits are engraved on the new left stone,
@@ -82,13 +87,16 @@ fn num_stones_after_n_blinks(initial: u64, blinks: usize) -> u64 {
         let left_half = initial / tens_power;
         let right_half = initial % tens_power;
 
-        return num_stones_after_n_blinks(left_half, blinks - 1)
-            + num_stones_after_n_blinks(right_half, blinks - 1);
+        return Ok(num_stones_after_n_blinks(left_half, blinks - 1)?
+            + num_stones_after_n_blinks(right_half, blinks - 1)?);
     }
 
     // If none of the other rules apply, the stone is replaced by a new stone;
     // the old stone's number multiplied by 2024 is engraved on the new stone.
-    num_stones_after_n_blinks(initial * 2024, blinks - 1)
+    let new_stone = initial
+        .checked_mul(2024)
+        .ok_or_else(|| format!("Overflow multiplying stone {initial} by 2024"))?;
+    num_stones_after_n_blinks(new_stone, blinks - 1)
 }
 
 #[cfg(test)]
@@ -100,4 +108,18 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(55312));
     }
+
+    #[test]
+    fn test_num_stones_overflow() {
+        // 19 digits, so the stone gets multiplied by 2024 which overflows
+        let result = num_stones_after_n_blinks(9_999_999_999_999_999_999, 1);
+        assert!(result.is_err());
+
+        assert_eq!(num_stones_after_n_blinks(125, 6), Ok(7));
+    }
+
+    #[test]
+    fn test_part_one_overflow() {
+        assert_eq!(part_one("9999999999999999999"), None);
+    }
 }
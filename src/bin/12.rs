@@ -182,26 +182,98 @@ fn find_regions(grid: &Grid<char>) -> Vec<Region> {
     regions
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
+// Pairs of indices (into find_regions) of regions which share a border,
+// with the lower index first
+#[cfg(test)]
+fn region_adjacency(input: &str) -> Vec<(usize, usize)> {
+    use std::collections::{BTreeSet, HashMap};
+
     let grid = parse(input);
+    let regions = find_regions(&grid);
+
+    let region_index: HashMap<Coord, usize> = regions
+        .iter()
+        .enumerate()
+        .flat_map(|(i, region)| region.0.iter().map(move |coord| (*coord, i)))
+        .collect();
 
-    let price = find_regions(&grid)
-        .into_iter()
-        .map(|region| region.compute_area() * region.compute_perimeter(&grid))
-        .sum();
+    let mut adjacency = BTreeSet::new();
+    for (coord, &i) in region_index.iter() {
+        for neighbor in grid.get_neighbors(coord) {
+            let j = region_index[&neighbor];
+            if i != j {
+                adjacency.insert((i.min(j), i.max(j)));
+            }
+        }
+    }
 
-    Some(price)
+    adjacency.into_iter().collect()
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+// Measurements of a single region which its price can be based on.
+// These are only computed when the cost function asks for them.
+struct RegionStats<'a> {
+    region: &'a Region,
+    grid: &'a Grid<char>,
+}
+
+impl RegionStats<'_> {
+    fn area(&self) -> u32 {
+        self.region.compute_area()
+    }
+
+    fn perimeter(&self) -> u32 {
+        self.region.compute_perimeter(self.grid)
+    }
+
+    fn sides(&self) -> u32 {
+        self.region.compute_number_of_sides(self.grid)
+    }
+}
+
+// Total price of all the regions, where the price of each region is given by cost
+#[cfg(test)]
+fn priced(input: &str, cost: impl Fn(&RegionStats) -> u32) -> u32 {
     let grid = parse(input);
 
-    let price = find_regions(&grid)
-        .into_iter()
-        .map(|region| region.compute_area() * region.compute_number_of_sides(&grid))
-        .sum();
+    find_regions(&grid)
+        .iter()
+        .map(|region| {
+            cost(&RegionStats {
+                region,
+                grid: &grid,
+            })
+        })
+        .sum()
+}
 
-    Some(price)
+// Find the regions once and compute the total price using both the perimeter
+// and the number of sides
+fn prices(input: &str) -> (u32, u32) {
+    let grid = parse(input);
+
+    find_regions(&grid)
+        .iter()
+        .map(|region| RegionStats {
+            region,
+            grid: &grid,
+        })
+        .fold((0, 0), |(perimeter_price, sides_price), stats| {
+            let area = stats.area();
+            (
+                perimeter_price + area * stats.perimeter(),
+                sides_price + area * stats.sides(),
+            )
+        })
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    Some(prices(input).0)
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    // Bulk discount
+    Some(prices(input).1)
 }
 
 #[cfg(test)]
@@ -216,6 +288,45 @@ mod tests {
         assert_eq!(result, Some(1930));
     }
 
+    #[test]
+    fn test_prices() {
+        let result = prices(&advent_of_code::template::read_file_part(
+            "examples", DAY, 1,
+        ));
+        assert_eq!(result, (1930, 1206));
+    }
+
+    #[test]
+    fn test_priced() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 1);
+
+        // Regions have areas 12, 4, 14, 10, 13, 11, 1, 13, 14, 5 and 3
+        assert_eq!(priced(&input, |stats| stats.area() * stats.area()), 1146);
+        assert_eq!(
+            priced(&input, |stats| stats.area() * stats.perimeter()),
+            1930
+        );
+        assert_eq!(priced(&input, |stats| stats.area() * stats.sides()), 1206);
+    }
+
+    #[test]
+    fn test_region_adjacency() {
+        // Regions are found column by column from the bottom left,
+        // so C is region 0, B is region 1 and A is region 2
+        let input = "\
+AAA
+BBB
+CCC";
+        assert_eq!(region_adjacency(input), [(0, 1), (1, 2)]);
+
+        // Region enclosed by another region
+        let input = "\
+AAA
+ABA
+AAA";
+        assert_eq!(region_adjacency(input), [(0, 1)]);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file_part(
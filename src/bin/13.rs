@@ -65,6 +65,39 @@ impl ClawMachine {
 
         None
     }
+
+    // Returns the determinant of the linear system (a.dx * b.dy - a.dy * b.dx) and whether
+    // the system has a unique solution, i.e. the determinant is nonzero.
+    // If it is zero, the buttons are parallel so there are either no solutions or infinitely many.
+    // Note a unique solution may still not be reachable, if it isn't a non-negative integer.
+    #[cfg(test)]
+    fn system_properties(&self) -> (i64, bool) {
+        let Button { dx: a_dx, dy: a_dy } = self.button_a;
+        let Button { dx: b_dx, dy: b_dy } = self.button_b;
+
+        let determinant = (a_dx * b_dy) as i64 - (a_dy * b_dx) as i64;
+        (determinant, determinant != 0)
+    }
+
+    // Solve the same linear system over the reals using Cramer's rule.
+    // The solution may not be integer (or even positive), in which case the prize
+    // can't actually be reached. Returns None if the buttons are parallel.
+    #[cfg(test)]
+    fn solve_rational(&self) -> Option<(f64, f64)> {
+        let (prize_x, prize_y) = (self.prize.0 as f64, self.prize.1 as f64);
+        let (a_dx, a_dy) = (self.button_a.dx as f64, self.button_a.dy as f64);
+        let (b_dx, b_dy) = (self.button_b.dx as f64, self.button_b.dy as f64);
+
+        let determinant = a_dx * b_dy - a_dy * b_dx;
+        if determinant == 0.0 {
+            return None;
+        }
+
+        let a = (prize_x * b_dy - prize_y * b_dx) / determinant;
+        let b = (a_dx * prize_y - a_dy * prize_x) / determinant;
+
+        Some((a, b))
+    }
 }
 
 fn parse(input: &str) -> Vec<ClawMachine> {
@@ -177,6 +210,52 @@ mod tests {
         assert_eq!(result, Some(480));
     }
 
+    #[test]
+    fn test_solve_rational() {
+        let claw_machine = ClawMachine {
+            button_a: Button { dx: 2, dy: 1 },
+            button_b: Button { dx: 1, dy: 2 },
+            prize: (4, 4),
+        };
+
+        // Real solution is a = b = 4/3 which is not reachable
+        assert_eq!(claw_machine.solve(), None);
+        let (a, b) = claw_machine.solve_rational().unwrap();
+        assert!((a - 4.0 / 3.0).abs() < 1e-9);
+        assert!((b - 4.0 / 3.0).abs() < 1e-9);
+
+        // Parallel buttons have no unique solution
+        let claw_machine = ClawMachine {
+            button_a: Button { dx: 1, dy: 1 },
+            button_b: Button { dx: 2, dy: 2 },
+            prize: (4, 4),
+        };
+        assert_eq!(claw_machine.solve_rational(), None);
+    }
+
+    #[test]
+    fn test_system_properties() {
+        let claw_machines = parse(&advent_of_code::template::read_file("examples", DAY));
+        let properties: Vec<(i64, bool)> = claw_machines
+            .iter()
+            .map(ClawMachine::system_properties)
+            .collect();
+
+        // Every example machine has a unique solution, even though only
+        // the first and third can actually win the prize in part one
+        assert_eq!(
+            properties,
+            [(5550, true), (-3876, true), (-6595, true), (4278, true)]
+        );
+
+        let parallel = ClawMachine {
+            button_a: Button { dx: 1, dy: 1 },
+            button_b: Button { dx: 2, dy: 2 },
+            prize: (4, 4),
+        };
+        assert_eq!(parallel.system_properties(), (0, false));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
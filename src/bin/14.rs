@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+#[cfg(test)]
+use advent_of_code::Grid;
 use advent_of_code::{Coord, Dimensions};
 use itertools::Itertools;
 
@@ -11,42 +13,79 @@ struct Robot {
 }
 
 impl Robot {
+    // Position after n seconds, without moving the robot
+    fn position_after(&self, dimensions: &Dimensions, n: usize) -> Coord {
+        let new_pos = self.pos + self.vel * n as i64;
+        dimensions.wrap_signed(&new_pos)
+    }
+
     fn step_n(&mut self, dimensions: &Dimensions, n: usize) {
-        let (dx, dy) = (self.vel.x * n as i64, self.vel.y * n as i64);
+        self.pos = self.position_after(dimensions, n);
+    }
+}
+
+// Return quadrant number where the quadrants are numbered like this
+// (starting from top left and going clockwise):
+// ..... .....
+// ..0.. ..1..
+// ..... .....
+//
+// ..... .....
+// ..3.. ..2..
+// ..... .....
+//
+// Note how we number the quadrants is not important as long as they are consistent
+fn quadrant(pos: &Coord, dimensions: &Dimensions) -> Option<usize> {
+    let mid_x = (dimensions.x / 2) as i64;
+    let mid_y = (dimensions.y / 2) as i64;
+
+    if pos.x < mid_x && pos.y < mid_y {
+        Some(0)
+    } else if pos.x > mid_x && pos.y < mid_y {
+        Some(1)
+    } else if pos.x > mid_x && pos.y > mid_y {
+        Some(2)
+    } else if pos.x < mid_x && pos.y > mid_y {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+// Positions of all the robots after the given number of seconds
+fn positions_at(robots: &[Robot], dimensions: &Dimensions, seconds: usize) -> Vec<Coord> {
+    robots
+        .iter()
+        .map(|robot| robot.position_after(dimensions, seconds))
+        .collect()
+}
 
-        let new_pos = self.pos.step(dx, dy);
-        let wrapped = dimensions.wrap(&new_pos);
+// Frame after the given number of seconds, where true marks a cell with at least one robot.
+// Uses the same top left origin as the input, so it can be drawn with Grid::render
+#[cfg(test)]
+fn frame_at(input: &str, dimensions: Dimensions, seconds: usize) -> Grid<bool> {
+    let robots = parse(input);
+    let mut frame = Grid::new(dimensions, vec![vec![false; dimensions.x]; dimensions.y]);
 
-        self.pos = wrapped;
+    for pos in positions_at(&robots, &dimensions, seconds) {
+        frame.set(&pos, true);
     }
 
-    // Return quadrant number where the quadrants are numbered like this
-    // (starting from top left and going clockwise):
-    // ..... .....
-    // ..0.. ..1..
-    // ..... .....
-    //
-    // ..... .....
-    // ..3.. ..2..
-    // ..... .....
-    //
-    // Note how we number the quadrants is not important as long as they are consistent
-    fn quadrant(&self, dimensions: &Dimensions) -> Option<usize> {
-        let mid_x = (dimensions.x / 2) as i64;
-        let mid_y = (dimensions.y / 2) as i64;
-
-        if self.pos.x < mid_x && self.pos.y < mid_y {
-            Some(0)
-        } else if self.pos.x > mid_x && self.pos.y < mid_y {
-            Some(1)
-        } else if self.pos.x > mid_x && self.pos.y > mid_y {
-            Some(2)
-        } else if self.pos.x < mid_x && self.pos.y > mid_y {
-            Some(3)
-        } else {
-            None
+    frame
+}
+
+// Number of robots in each quadrant after the given number of seconds,
+// indexed by the quadrant numbers above. Robots on the middle lines are not counted.
+fn quadrant_counts(robots: &[Robot], dimensions: &Dimensions, seconds: usize) -> [u32; 4] {
+    let mut counts = [0; 4];
+
+    for pos in positions_at(robots, dimensions, seconds) {
+        if let Some(quadrant) = quadrant(&pos, dimensions) {
+            counts[quadrant] += 1;
         }
     }
+
+    counts
 }
 
 fn parse(input: &str) -> Vec<Robot> {
@@ -86,7 +125,7 @@ fn parse_robot(input: &str) -> Robot {
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut robots = parse(input);
+    let robots = parse(input);
 
     // The example has different dimensions
     #[cfg(test)]
@@ -94,19 +133,9 @@ pub fn part_one(input: &str) -> Option<u32> {
     #[cfg(not(test))]
     let dimensions = Dimensions::new(101, 103);
 
-    let mut quadrant_counts = [0, 0, 0, 0];
+    let safety_factor = quadrant_counts(&robots, &dimensions, 100).iter().product();
 
-    for robot in robots.iter_mut() {
-        robot.step_n(&dimensions, 100);
-
-        if let Some(quadrant) = robot.quadrant(&dimensions) {
-            quadrant_counts[quadrant] += 1;
-        }
-    }
-
-    let safety_factor =
-        quadrant_counts[0] * quadrant_counts[1] * quadrant_counts[2] * quadrant_counts[3];
-    Some(safety_factor as u32)
+    Some(safety_factor)
 }
 
 // Return the max number of consecutive robots horizontally
@@ -180,4 +209,37 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(12));
     }
+
+    #[test]
+    fn test_quadrant_counts() {
+        let robots = parse(&advent_of_code::template::read_file("examples", DAY));
+        let counts = quadrant_counts(&robots, &Dimensions::new(11, 7), 100);
+
+        // 1 robot top left, 3 top right, 1 bottom right, 4 bottom left
+        assert_eq!(counts, [1, 3, 1, 4]);
+        assert_eq!(counts.iter().product::<u32>(), 12);
+    }
+
+    #[test]
+    fn test_frame_at() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let frame = frame_at(&input, Dimensions::new(11, 7), 100);
+
+        // Some robots overlap after 100 seconds, so there are fewer occupied cells than robots
+        let occupied = frame.iter().filter(|(_, robot)| **robot).count();
+        assert!(occupied <= input.lines().count());
+        assert_eq!(occupied, 10);
+
+        assert_eq!(
+            frame.render(|robot| if *robot { '#' } else { '.' }),
+            "\
+......#..#.
+...........
+#..........
+.##........
+.....#.....
+...##......
+.#....#...."
+        );
+    }
 }
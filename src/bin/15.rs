@@ -32,6 +32,27 @@ impl Grid {
         }
     }
 
+    // Out of bounds is treated as a wall, in case the map is not enclosed by walls
+    fn is_blocked(&self, coord: &Coord) -> bool {
+        self.walls.contains(coord) || !self.dimensions.in_bounds(coord)
+    }
+
+    // Check no box has been pushed out of bounds
+    // For part 2, both halves of each box must be in bounds
+    #[cfg(test)]
+    fn assert_boxes_in_bounds(&self, wide_boxes: bool) {
+        for box_ in self.boxes.iter() {
+            assert!(self.dimensions.in_bounds(box_), "Box {box_} out of bounds");
+            if wide_boxes {
+                let right_half = Coord::new(box_.x + 1, box_.y);
+                assert!(
+                    self.dimensions.in_bounds(&right_half),
+                    "Box {right_half} out of bounds"
+                );
+            }
+        }
+    }
+
     fn expand_for_part_two(self) -> Self {
         let Grid {
             dimensions,
@@ -40,7 +61,7 @@ impl Grid {
             robot,
         } = self;
 
-        let new_dimensions = Dimensions::new(dimensions.x * 2, dimensions.y);
+        let new_dimensions = dimensions.scaled(2, 1);
         let new_walls = walls
             .into_iter()
             .flat_map(|coord| {
@@ -147,15 +168,15 @@ fn parse_movements(input: &str) -> Vec<Direction> {
 }
 
 fn parse_movement(c: char) -> Direction {
-    match c {
-        // Because our coordinate system uses (0, 0) as the top left,
-        // ^ actually means to decrease the y-coordinate while v means to increase it,
-        // which is the opposite of the convention so we swap the directions here
-        '^' => Direction::Down,
-        'v' => Direction::Up,
-        '<' => Direction::Left,
-        '>' => Direction::Right,
-        c => panic!("Unexpected char {c} could not be parsed as movement"),
+    let dir = Direction::from_char(c)
+        .unwrap_or_else(|| panic!("Unexpected char {c} could not be parsed as movement"));
+
+    // Because our coordinate system uses (0, 0) as the top left,
+    // ^ actually means to decrease the y-coordinate while v means to increase it,
+    // which is the opposite of the convention so we swap the vertical directions here
+    match dir {
+        Direction::Up | Direction::Down => dir.opposite(),
+        Direction::Left | Direction::Right => dir,
     }
 }
 
@@ -187,7 +208,7 @@ pub fn part_one(input: &str) -> Option<u32> {
         }
 
         // At the end of the line of boxes:
-        if !map.walls.contains(&coord) {
+        if !map.is_blocked(&coord) {
             // If there is an empty space, push all the boxes. The result is
             // that the robot moves over, the first box is removed and the last box
             // is added.
@@ -203,6 +224,8 @@ pub fn part_one(input: &str) -> Option<u32> {
 
         #[cfg(test)]
         map.print();
+        #[cfg(test)]
+        map.assert_boxes_in_bounds(false);
     }
 
     let sum = map.boxes.iter().map(compute_gps_coordinate).sum();
@@ -247,7 +270,7 @@ pub fn part_two(input: &str) -> Option<u32> {
                         queue.push_back(right_half.step_in_direction(dir));
                     }
                 }
-            } else if map.walls.contains(&coord) {
+            } else if map.is_blocked(&coord) {
                 hit_wall = true;
                 break;
             }
@@ -275,6 +298,8 @@ pub fn part_two(input: &str) -> Option<u32> {
 
         #[cfg(test)]
         map.print_for_part_two();
+        #[cfg(test)]
+        map.assert_boxes_in_bounds(true);
     }
 
     let sum = map.boxes.iter().map(compute_gps_coordinate).sum();
@@ -317,4 +342,18 @@ mod tests {
         let expected = 105 + 207 + 306;
         assert_eq!(result, Some(expected));
     }
+
+    #[test]
+    fn test_open_edged_map() {
+        // No walls, so the edges of the map must stop the boxes and robot
+        let input = "\
+....
+.O@.
+
+<<<>>>>>>vvv^^^";
+
+        // Box is pushed to (0, 1) and stops there
+        assert_eq!(part_one(input), Some(100));
+        assert_eq!(part_two(input), Some(100));
+    }
 }
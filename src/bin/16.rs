@@ -1,6 +1,12 @@
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::HashSet;
 
-use advent_of_code::{parse_maze, Coord, Direction, Maze};
+use advent_of_code::{dijkstra, dijkstra_all_paths, parse_maze, Coord, Direction, Maze};
+
+// Only needed by the helpers which are kept for tests
+#[cfg(test)]
+use advent_of_code::{dijkstra_costs, Grid, MazeBuilder};
+#[cfg(test)]
+use std::collections::HashMap;
 
 advent_of_code::solution!(16);
 
@@ -56,86 +62,94 @@ fn test_state() {
     assert_eq!(state.turn_right().step().pos, Coord::new(10, 9));
 }
 
-// Helper struct to use in BinaryHeap to make it a priority queue
-#[derive(Clone, Copy, PartialEq, Eq)]
-struct PqState {
-    score_so_far: usize, // score to get to the current state
-    state: State,
+// Find the lowest score to reach each reachable state from the start using Dijkstra's algorithm
+// Unlike find_lowest_score, this searches the whole maze instead of stopping at the end
+#[cfg(test)]
+fn scores_from_start(maze: &Maze) -> HashMap<State, usize> {
+    dijkstra_costs(State::start_state(maze), |state| successors(maze, state))
 }
 
-// Make it a min-heap so we can explore lower cost paths first
-impl Ord for PqState {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.score_so_far.cmp(&self.score_so_far)
+// Build a maze from an already parsed char grid, e.g. one with (0, 0) at the top left
+// like day 15 uses. Flipping the maze vertically doesn't change any scores, since turning
+// left and right cost the same.
+#[cfg(test)]
+fn maze_from_grid(grid: &Grid<char>, wall: char, start: char, end: char) -> Maze {
+    let mut builder = MazeBuilder::with_dimensions(grid.dimensions);
+    for (coord, c) in grid.iter() {
+        if *c == wall {
+            builder = builder.wall(coord);
+        } else if *c == start {
+            builder = builder.start(coord);
+        } else if *c == end {
+            builder = builder.end(coord);
+        }
     }
+
+    builder.build()
 }
 
-impl PartialOrd for PqState {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+// States which can be reached from state in one move, along with the cost of the move
+fn successors(maze: &Maze, state: &State) -> Vec<(State, usize)> {
+    [
+        (state.step(), 1),
+        (state.turn_left().step(), 1001), // we turned and stepped so 1000 + 1
+        (state.turn_right().step(), 1001),
+    ]
+    .into_iter()
+    .filter(|(new_state, _)| !maze.walls.contains(&new_state.pos))
+    .collect()
 }
 
 fn find_lowest_score(maze: &Maze) -> Option<usize> {
-    let start_pq_state = PqState {
-        score_so_far: 0,
-        state: State::start_state(maze),
-    };
+    if maze.start == maze.end {
+        // Already at the end without moving
+        return Some(0);
+    }
 
-    // Use heap as a priority queue
-    let mut queue = BinaryHeap::new();
-    queue.push(start_pq_state);
-
-    let mut visited = HashSet::new();
-
-    // Start from the lowest score so far
-    while let Some(PqState {
-        score_so_far,
-        state,
-    }) = queue.pop()
-    {
-        if state.pos == maze.end {
-            // We've reached the end, return the score
-            return Some(score_so_far);
-        }
+    // The end can be reached facing any direction
+    dijkstra(
+        State::start_state(maze),
+        |state| state.pos == maze.end,
+        |state| successors(maze, state),
+    )
+}
+
+// Variant of find_lowest_score which forbids revisiting any cell, regardless of direction.
+// This can change the answer for mazes where looping back through a cell is the
+// cheapest way to turn around.
+#[cfg(test)]
+fn find_lowest_simple_path(maze: &Maze) -> Option<usize> {
+    use std::hash::{Hash, Hasher};
+
+    // State which only compares by position, so a search visits each cell at most once
+    // regardless of the direction it is reached facing
+    #[derive(Clone, Copy)]
+    struct CellState(State);
 
-        if visited.contains(&state) {
-            // we have already checked this state and didn't find a solution
-            continue;
+    impl PartialEq for CellState {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.pos == other.0.pos
         }
-        visited.insert(state);
-
-        // Try stepping in each direction
-        for new_state in [
-            state.step(),
-            state.turn_left().step(),
-            state.turn_right().step(),
-        ] {
-            if maze.walls.contains(&new_state.pos) {
-                // hit a wall
-                continue;
-            }
+    }
 
-            if visited.contains(&new_state) {
-                // we have already checked this state and didn't find a solution
-                continue;
-            }
+    impl Eq for CellState {}
 
-            let cost = if new_state.dir == state.dir {
-                1
-            } else {
-                1001 // we turned and stepped so 1000 + 1
-            };
-
-            // Add to the priority queue
-            queue.push(PqState {
-                score_so_far: score_so_far + cost,
-                state: new_state,
-            });
+    impl Hash for CellState {
+        fn hash<H: Hasher>(&self, hasher: &mut H) {
+            self.0.pos.hash(hasher);
         }
     }
 
-    None
+    dijkstra(
+        CellState(State::start_state(maze)),
+        |CellState(state)| state.pos == maze.end,
+        |CellState(state)| {
+            successors(maze, state)
+                .into_iter()
+                .map(|(new_state, cost)| (CellState(new_state), cost))
+                .collect()
+        },
+    )
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -146,106 +160,81 @@ pub fn part_one(input: &str) -> Option<u32> {
     Some(score as u32)
 }
 
-// Helper struct to use in BinaryHeap to make it a priority queue
-#[derive(Clone, PartialEq, Eq)]
-struct PqState2 {
-    score_so_far: usize, // score to get to the current state
-    state: State,
-    path: HashSet<Coord>,
-}
-
-// Make it a min-heap so we can explore lower cost paths first
-impl Ord for PqState2 {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.score_so_far.cmp(&self.score_so_far)
-    }
-}
-
-impl PartialOrd for PqState2 {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+fn find_lowest_score_seats(maze: &Maze) -> HashSet<Coord> {
+    if maze.start == maze.end {
+        // The only best path is to not move, which uses just the start tile
+        return HashSet::from([maze.start]);
     }
-}
 
-fn find_lowest_score_seats(maze: &Maze) -> HashSet<Coord> {
-    let start_pq_state = PqState2 {
-        score_so_far: 0,
-        state: State::start_state(maze),
-        path: HashSet::from([maze.start]),
+    let Some((_, best_states)) = dijkstra_all_paths(
+        State::start_state(maze),
+        |state| state.pos == maze.end,
+        |state| successors(maze, state),
+    ) else {
+        return HashSet::new();
     };
 
-    // Use heap as a priority queue
-    let mut queue = BinaryHeap::new();
-    queue.push(start_pq_state);
-
-    let mut best_score = None;
-
-    let mut best_seats = HashSet::new();
-
-    // Optimize by pruning the search if we have already found a lower score
-    // way to reach this node
-    let mut min_score_to_node = HashMap::new();
+    // The same seat may be on a best path facing several directions
+    best_states.into_iter().map(|state| state.pos).collect()
+}
 
-    // Start from the lowest score so far
-    while let Some(PqState2 {
-        score_so_far,
-        state,
-        path,
-    }) = queue.pop()
-    {
-        if best_score.is_some() && score_so_far > best_score.unwrap() {
-            // All other paths are longer than the best path, so we can stop searching
-            break;
+// Returns (number of tiles on any best path, number of distinct best paths)
+// Goes through the states in order of their lowest score from the start, counting how many
+// lowest score paths reach each state and which states they come from, then backtracks
+// from the end to find the tiles
+#[cfg(test)]
+fn best_path_stats(maze: &Maze) -> (usize, u64) {
+    let scores = scores_from_start(maze);
+
+    // All predecessors on a lowest score path have a strictly lower score,
+    // so each count is final by the time the state is reached
+    let mut states_by_score: Vec<State> = scores.keys().copied().collect();
+    states_by_score.sort_by_key(|state| scores[state]);
+
+    let mut path_counts = HashMap::from([(State::start_state(maze), 1)]);
+    let mut predecessors: HashMap<State, Vec<State>> = HashMap::new();
+    for state in states_by_score {
+        let path_count = path_counts[&state];
+
+        for (new_state, cost) in successors(maze, &state) {
+            if scores[&state] + cost == scores[&new_state] {
+                // a lowest score path to new_state
+                *path_counts.entry(new_state).or_default() += path_count;
+                predecessors.entry(new_state).or_default().push(state);
+            }
         }
+    }
 
-        if state.pos == maze.end {
-            // We've reached the end, set the best score
-            best_score = Some(score_so_far);
-            best_seats = best_seats.union(&path).cloned().collect();
-            continue;
-        }
+    // The end can be reached facing any direction
+    let end_states: Vec<State> = Direction::all()
+        .map(|dir| State { pos: maze.end, dir })
+        .filter(|state| scores.contains_key(state))
+        .collect();
 
-        if min_score_to_node.contains_key(&state)
-            && *min_score_to_node.get(&state).unwrap() < score_so_far
-        {
-            // We have already explored this state with a lower score so this can't be the best path
-            continue;
-        }
-        min_score_to_node.insert(state, score_so_far);
-
-        // Try stepping in each direction
-        for new_state in [
-            state.step(),
-            state.turn_left().step(),
-            state.turn_right().step(),
-        ] {
-            if maze.walls.contains(&new_state.pos) {
-                // hit a wall
-                continue;
+    let Some(best_score) = end_states.iter().map(|state| scores[state]).min() else {
+        return (0, 0);
+    };
+    let best_end_states: Vec<State> = end_states
+        .into_iter()
+        .filter(|state| scores[state] == best_score)
+        .collect();
+
+    let num_paths = best_end_states.iter().map(|state| path_counts[state]).sum();
+
+    // Backtrack from the end to find all the states on a best path
+    let mut visited: HashSet<State> = best_end_states.iter().copied().collect();
+    let mut stack = best_end_states;
+    while let Some(state) = stack.pop() {
+        for predecessor in predecessors.get(&state).into_iter().flatten() {
+            if visited.insert(*predecessor) {
+                stack.push(*predecessor);
             }
-
-            let cost = if new_state.dir == state.dir {
-                1
-            } else {
-                1001 // we turned and stepped so 1000 + 1
-            };
-
-            let new_path = {
-                let mut new_path = path.clone();
-                new_path.insert(new_state.pos);
-                new_path
-            };
-
-            // Add to the priority queue
-            queue.push(PqState2 {
-                score_so_far: score_so_far + cost,
-                state: new_state,
-                path: new_path,
-            });
         }
     }
 
-    best_seats
+    let tiles: HashSet<Coord> = visited.into_iter().map(|state| state.pos).collect();
+
+    (tiles.len(), num_paths)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -259,6 +248,7 @@ pub fn part_two(input: &str) -> Option<u32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::Dimensions;
 
     #[test]
     fn test_part_one_1() {
@@ -276,6 +266,94 @@ mod tests {
         assert_eq!(result, Some(11048));
     }
 
+    #[test]
+    fn test_scores_from_start() {
+        let maze = parse_maze(&advent_of_code::template::read_file_part(
+            "examples", DAY, 1,
+        ));
+        let scores = scores_from_start(&maze);
+
+        assert_eq!(scores.get(&State::start_state(&maze)), Some(&0));
+
+        // The end can be reached facing any direction
+        let lowest_end_score = Direction::all()
+            .filter_map(|dir| scores.get(&State { pos: maze.end, dir }))
+            .min();
+        assert_eq!(lowest_end_score, Some(&7036));
+        assert_eq!(find_lowest_score(&maze), Some(7036));
+    }
+
+    #[test]
+    fn test_maze_from_grid() {
+        let grid = Grid::from_input(
+            "\
+#####
+#@..#
+###E#
+#####",
+            |c| c,
+        );
+        let maze = maze_from_grid(&grid, '#', '@', 'E');
+        assert_eq!(maze.start, Coord::new(1, 1));
+        assert_eq!(maze.end, Coord::new(3, 2));
+        assert_eq!(maze.walls.len(), 16);
+        assert_eq!(find_lowest_score(&maze), Some(1003));
+
+        // Same score as parse_maze, even though the grid is upside down
+        let input = advent_of_code::template::read_file_part("examples", DAY, 1);
+        let maze = maze_from_grid(&Grid::from_input(&input, |c| c), '#', 'S', 'E');
+        assert_eq!(find_lowest_score(&maze), Some(7036));
+    }
+
+    #[test]
+    fn test_find_lowest_simple_path() {
+        // Starting facing east, the only way to reach the end is to loop around
+        // the room to the east and come back through the start
+        let maze = parse_maze(
+            "\
+########
+#E..S..#
+####...#
+########",
+        );
+
+        assert_eq!(find_lowest_score(&maze), Some(4007));
+        assert_eq!(find_lowest_simple_path(&maze), None);
+
+        // Without loops, both agree
+        let maze = parse_maze(&advent_of_code::template::read_file_part(
+            "examples", DAY, 1,
+        ));
+        assert_eq!(find_lowest_simple_path(&maze), Some(7036));
+    }
+
+    #[test]
+    fn test_start_is_end() {
+        let dimensions = Dimensions::new(3, 3);
+        let center = Coord::new(1, 1);
+        let mut builder = MazeBuilder::with_dimensions(dimensions)
+            .start(center)
+            .end(center);
+        for coord in dimensions.iter_coords().filter(|coord| *coord != center) {
+            builder = builder.wall(coord);
+        }
+        let maze = builder.build();
+
+        assert_eq!(find_lowest_score(&maze), Some(0));
+        assert_eq!(find_lowest_score_seats(&maze), HashSet::from([center]));
+        assert_eq!(best_path_stats(&maze), (1, 1));
+
+        // Adjacent, facing the end
+        let maze = parse_maze(
+            "\
+####
+#SE#
+####",
+        );
+        assert_eq!(find_lowest_score(&maze), Some(1));
+        assert_eq!(find_lowest_score_seats(&maze).len(), 2);
+    }
+
     #[test]
     fn test_part_two_1() {
         let result = part_two(&advent_of_code::template::read_file_part(
@@ -284,6 +362,21 @@ mod tests {
         assert_eq!(result, Some(45));
     }
 
+    #[test]
+    fn test_best_path_stats() {
+        let maze = parse_maze(&advent_of_code::template::read_file_part(
+            "examples", DAY, 1,
+        ));
+        // 3 distinct best paths share the 45 tiles marked in the puzzle description
+        assert_eq!(best_path_stats(&maze), (45, 3));
+        assert_eq!(find_lowest_score_seats(&maze).len(), 45);
+
+        let maze = parse_maze(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(best_path_stats(&maze), (64, 2));
+    }
+
     #[test]
     fn test_part_two_2() {
         let result = part_two(&advent_of_code::template::read_file_part(
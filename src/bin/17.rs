@@ -55,6 +55,14 @@ pub fn part_one(input: &str) -> Option<String> {
     Some(output.into_iter().join(","))
 }
 
+// Run the program from the input, overriding the initial values of all three registers
+#[cfg(test)]
+fn run_with_registers(input: &str, a: u64, b: u64, c: u64) -> Vec<u8> {
+    let (_registers, program) = parse(input);
+
+    run_program(&mut [a, b, c], program)
+}
+
 fn run_program(registers: &mut [u64; 3], program: Vec<u8>) -> Vec<u8> {
     // Instruction pointer
     let mut ip = 0;
@@ -62,59 +70,83 @@ fn run_program(registers: &mut [u64; 3], program: Vec<u8>) -> Vec<u8> {
     let mut output = Vec::new();
 
     while ip < program.len() - 1 {
-        // These are safe to unwrap since we check the bounds in the while condition
-        let opcode = *program.get(ip).unwrap();
-        let operand = *program.get(ip + 1).unwrap();
-
-        match opcode {
-            0 => {
-                // adv (division)
-                registers[0] >>= combo_operand(operand, registers);
-            }
-            1 => {
-                // bxl (bitwise xor)
-                let left = registers[1];
-                let right = operand;
-                registers[1] = left ^ right as u64;
-            }
-            2 => {
-                // bst (mod 8)
-                let left = combo_operand(operand, registers);
-                registers[1] = left % 8;
-            }
-            3 => {
-                // jnz (jump)
-                if registers[0] != 0 {
-                    ip = operand as usize;
-                    continue; // don't increment the ip
-                }
-            }
-            4 => {
-                // bxc (bitwise xor)
-                let left = registers[1];
-                let right = registers[2];
-                registers[1] = left ^ right;
-            }
-            5 => {
-                // out (combo operand mod 8)
-                let left = combo_operand(operand, registers);
-                output.push((left % 8) as u8);
-            }
-            6 => {
-                // bdv (division)
-                registers[1] = registers[0] >> combo_operand(operand, registers);
-            }
-            7 => {
-                // cdv (division)
-                registers[2] = registers[0] >> combo_operand(operand, registers);
+        let (new_ip, out) = execute(registers, &program, ip);
+        output.extend(out);
+        ip = new_ip;
+    }
+
+    output
+}
+
+// Run the program, recording after each executed instruction the registers,
+// the instruction pointer (of the next instruction to execute), and the output, if any
+#[cfg(test)]
+fn trace(regs: [u64; 3], program: &[u8]) -> Vec<([u64; 3], usize, Option<u8>)> {
+    let mut registers = regs;
+    let mut ip = 0;
+
+    let mut trace = Vec::new();
+
+    while ip < program.len().saturating_sub(1) {
+        let (new_ip, out) = execute(&mut registers, program, ip);
+        trace.push((registers, new_ip, out));
+        ip = new_ip;
+    }
+
+    trace
+}
+
+// Execute the instruction at ip, returning the new instruction pointer and the output, if any
+// Caller must check that ip is in bounds
+fn execute(registers: &mut [u64; 3], program: &[u8], ip: usize) -> (usize, Option<u8>) {
+    let opcode = program[ip];
+    let operand = program[ip + 1];
+
+    match opcode {
+        0 => {
+            // adv (division)
+            registers[0] >>= combo_operand(operand, registers);
+        }
+        1 => {
+            // bxl (bitwise xor)
+            let left = registers[1];
+            let right = operand;
+            registers[1] = left ^ right as u64;
+        }
+        2 => {
+            // bst (mod 8)
+            let left = combo_operand(operand, registers);
+            registers[1] = left % 8;
+        }
+        3 => {
+            // jnz (jump)
+            if registers[0] != 0 {
+                return (operand as usize, None); // don't increment the ip
             }
-            opcode => panic!("Invalid opcode {opcode}"),
         }
-
-        ip += 2;
+        4 => {
+            // bxc (bitwise xor)
+            let left = registers[1];
+            let right = registers[2];
+            registers[1] = left ^ right;
+        }
+        5 => {
+            // out (combo operand mod 8)
+            let left = combo_operand(operand, registers);
+            return (ip + 2, Some((left % 8) as u8));
+        }
+        6 => {
+            // bdv (division)
+            registers[1] = registers[0] >> combo_operand(operand, registers);
+        }
+        7 => {
+            // cdv (division)
+            registers[2] = registers[0] >> combo_operand(operand, registers);
+        }
+        opcode => panic!("Invalid opcode {opcode}"),
     }
 
-    output
+    (ip + 2, None)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
@@ -370,6 +402,11 @@ mod bits_2 {
 mod tests {
     use super::*;
 
+    // Whether running the program with register A set to reg_a outputs the program itself
+    fn produces_self(reg_a: u64, program: &[u8]) -> bool {
+        run_program(&mut [reg_a, 0, 0], program.to_vec()) == program
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file_part(
@@ -378,6 +415,39 @@ mod tests {
         assert_eq!(result, Some("4,6,3,5,6,3,5,2,1,0".to_string()));
     }
 
+    #[test]
+    fn test_run_with_registers() {
+        // The part one program only reads from register A, so B and C don't affect the output
+        let input = advent_of_code::template::read_file_part("examples", DAY, 1);
+        assert_eq!(
+            run_with_registers(&input, 729, 5, 9),
+            vec![4, 6, 3, 5, 6, 3, 5, 2, 1, 0]
+        );
+
+        // Output B % 8 and C % 8, then B ^ C
+        let input = "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 5,5,5,6,4,0,5,5";
+        assert_eq!(run_with_registers(input, 0, 13, 6), vec![5, 6, 3]);
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 1);
+        let (registers, program) = parse(&input);
+        let trace = trace(registers, &program);
+
+        // Each loop is adv, out, jnz, and there is one loop per output value
+        assert_eq!(trace.len(), 30);
+        assert_eq!(trace[0], ([364, 0, 0], 2, None));
+        assert_eq!(trace[1], ([364, 0, 0], 4, Some(4)));
+        assert_eq!(trace[2], ([364, 0, 0], 0, None));
+
+        // 729 is shifted right once per loop, then the jnz falls through
+        assert_eq!(trace.last(), Some(&([0, 0, 0], 6, None)));
+
+        let output: Vec<u8> = trace.iter().filter_map(|(_, _, out)| *out).collect();
+        assert_eq!(output, run_program(&mut registers.clone(), program));
+    }
+
     // Check answer for part 2
     // Note we stored the actual puzzle input in 17-2.txt
     #[test]
@@ -390,6 +460,21 @@ mod tests {
         assert_eq!(run_program(&mut registers, program.clone()), program);
     }
 
+    // Check the solver's answer actually reproduces the program
+    // Note we stored the actual puzzle input in 17-2.txt
+    #[test]
+    fn test_find_lowest_quine_produces_self() {
+        let input = advent_of_code::template::read_file_part("examples", DAY, 2);
+        let (_registers, program) = parse(&input);
+
+        let reg_a = part_two(&input).unwrap();
+        assert!(produces_self(reg_a, &program));
+
+        // Off by one in either direction should not work
+        assert!(!produces_self(reg_a - 1, &program));
+        assert!(!produces_self(reg_a + 1, &program));
+    }
+
     // Check different representations for part 2
     // Note we stored the actual puzzle input in 17-2.txt
     #[test]
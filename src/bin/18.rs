@@ -1,22 +1,15 @@
 use std::collections::HashSet;
 
-use advent_of_code::{Coord, Dimensions};
-use itertools::Itertools;
+use advent_of_code::{Coord, Dimensions, Side};
 
 advent_of_code::solution!(18);
 
 fn parse_bytes(input: &str) -> Vec<Coord> {
     input
         .lines()
-        .map(|line| {
-            line.split(",")
-                .map(str::parse)
-                .map(|res| res.unwrap_or_else(|_| panic!("Failed to parse ints from {line}")))
-                .collect_tuple::<(i64, i64)>()
-                .unwrap_or_else(|| panic!("Did not find 2 numbers in {line}"))
-                .into()
-        })
-        .collect::<Vec<_>>()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|e| panic!("Failed to parse bytes: {e}"))
 }
 
 #[derive(Debug)]
@@ -98,10 +91,6 @@ pub fn part_one(input: &str) -> Option<u32> {
 }
 
 pub fn part_two(input: &str) -> Option<String> {
-    // Search for a path of walls from the left or bottom border to the right or top border.
-    // If a path can be found, then the end is not reachable.
-    let bytes = parse_bytes(input);
-
     #[cfg(test)]
     let dimensions = Dimensions::new(7, 7);
     #[cfg(not(test))]
@@ -109,27 +98,41 @@ pub fn part_two(input: &str) -> Option<String> {
 
     // We know a path exists for 12/1024 so start searching there
     #[cfg(test)]
-    let i = 12;
+    let initial = 12;
     #[cfg(not(test))]
-    let i = 1024;
+    let initial = 1024;
+
+    first_blocking_byte(input, dimensions, initial).map(|coord| format!("{},{}", coord.x, coord.y))
+}
+
+// Find the first byte that blocks the path to the end, assuming the path is not yet blocked
+// after the initial number of bytes
+fn first_blocking_byte(input: &str, dimensions: Dimensions, initial: usize) -> Option<Coord> {
+    // Search for a path of walls from the left or bottom border to the right or top border.
+    // If a path can be found, then the end is not reachable.
+    let bytes = parse_bytes(input);
 
-    let mut walls: HashSet<Coord> = HashSet::from_iter(bytes.iter().copied().take(i));
+    let mut walls: HashSet<Coord> = HashSet::from_iter(bytes.iter().copied().take(initial));
 
-    let starts: HashSet<Coord> = dimensions
-        .left_borders()
-        .chain(dimensions.top_borders())
-        .collect();
-    let ends: HashSet<Coord> = dimensions
-        .right_borders()
-        .chain(dimensions.bottom_borders())
-        .collect();
+    let is_start = |coord: &Coord| {
+        dimensions
+            .which_borders(coord)
+            .iter()
+            .any(|side| matches!(side, Side::Left | Side::Top))
+    };
+    let is_end = |coord: &Coord| {
+        dimensions
+            .which_borders(coord)
+            .iter()
+            .any(|side| matches!(side, Side::Right | Side::Bottom))
+    };
 
     // Compute and keep track of a set of walls reachable from the left or bottom borders using DFS.
     // For each new wall that is added, we only need to recompute the "reachable" walls if
     // the new wall is a neighbor of an existing reachable wall.
-    let mut reachable: HashSet<Coord> = walls.intersection(&starts).copied().collect();
+    let mut reachable: HashSet<Coord> = walls.iter().copied().filter(is_start).collect();
 
-    for (i, new_wall) in bytes.into_iter().enumerate().skip(i) {
+    for (i, new_wall) in bytes.into_iter().enumerate().skip(initial) {
         // Add the wall
         walls.insert(new_wall);
 
@@ -138,13 +141,11 @@ pub fn part_two(input: &str) -> Option<String> {
             // For the first iteration, we need to compute all reachable
             true
         } else {
-            let mut new_wall_neighbors = dimensions
-                .get_neighbors(&new_wall)
-                .chain(dimensions.get_diagonal_neighbors(&new_wall));
+            let mut new_wall_neighbors = dimensions.get_all_neighbors(&new_wall);
 
             // We only need to recompute if this wall is attached to the current "reachable" set,
             // or if it is a new starting wall
-            new_wall_neighbors.any(|c| reachable.contains(&c)) || starts.contains(&new_wall)
+            new_wall_neighbors.any(|c| reachable.contains(&c)) || is_start(&new_wall)
         };
 
         if !recompute {
@@ -158,10 +159,7 @@ pub fn part_two(input: &str) -> Option<String> {
             let mut new_reachable = HashSet::new();
             for wall in reachable.iter() {
                 // Check all neighbors of current reachable walls
-                for neighbor in dimensions
-                    .get_neighbors(wall)
-                    .chain(dimensions.get_diagonal_neighbors(wall))
-                {
+                for neighbor in dimensions.get_all_neighbors(wall) {
                     if !walls.contains(&neighbor) {
                         // This neighbor is not a wall so it doesn't contribute to our path
                         continue;
@@ -184,18 +182,43 @@ pub fn part_two(input: &str) -> Option<String> {
             reachable.extend(new_reachable);
         }
 
-        if reachable.intersection(&ends).next().is_some() {
+        if reachable.iter().any(is_end) {
             // One of the ends (right/top border) is reachable, so we have found the solution
             print_grid(&dimensions, &reachable, 'R');
             print_grid(&dimensions, &walls, 'W');
-            let output = format!("{},{}", new_wall.x, new_wall.y);
-            return Some(output);
+            return Some(new_wall);
         }
     }
 
     None
 }
 
+// Every cell reachable from the start (top left) after the given number of bytes have fallen
+#[cfg(test)]
+fn reachable_from_start(input: &str, byte_count: usize, dimensions: Dimensions) -> HashSet<Coord> {
+    use std::collections::VecDeque;
+
+    let walls: HashSet<Coord> = parse_bytes(input).into_iter().take(byte_count).collect();
+
+    let start = dimensions.small_corner();
+    if walls.contains(&start) {
+        return HashSet::new();
+    }
+
+    // BFS
+    let mut reachable = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(pos) = queue.pop_front() {
+        for neighbor in dimensions.get_neighbors(&pos) {
+            if !walls.contains(&neighbor) && reachable.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    reachable
+}
+
 // Print a grid for debugging
 #[allow(dead_code)]
 fn print_grid(dimensions: &Dimensions, set: &HashSet<Coord>, c: char) {
@@ -227,4 +250,31 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some("6,1".to_string()));
     }
+
+    #[test]
+    fn test_first_blocking_byte() {
+        let result = first_blocking_byte(
+            &advent_of_code::template::read_file("examples", DAY),
+            Dimensions::new(7, 7),
+            12,
+        );
+        assert_eq!(result, Some(Coord::new(6, 1)));
+    }
+
+    #[test]
+    fn test_reachable_from_start() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let dimensions = Dimensions::new(7, 7);
+        let end = dimensions.large_corner();
+
+        let reachable = reachable_from_start(&input, 12, dimensions);
+        assert!(reachable.contains(&end));
+        assert!(reachable.contains(&dimensions.small_corner()));
+
+        // The 21st byte at 6,1 is the first to block the path
+        assert!(reachable_from_start(&input, 20, dimensions).contains(&end));
+        let reachable = reachable_from_start(&input, 21, dimensions);
+        assert!(!reachable.contains(&end));
+        assert!(!reachable.contains(&Coord::new(6, 1)));
+    }
 }
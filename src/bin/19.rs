@@ -62,18 +62,99 @@ fn design_is_possible(
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
+    let ways = ways_per_design(input)
+        .into_iter()
+        .map(|(_, ways)| ways)
+        .sum();
+
+    Some(ways)
+}
+
+// Number of ways to make each design, in the order of the input
+fn ways_per_design(input: &str) -> Vec<(String, u64)> {
     let (patterns, designs) = parse(input);
+    let trie = Trie::new(&patterns);
 
     let mut memo = HashMap::new();
-    let ways = designs
+    designs
         .into_iter()
-        .map(|design| design_ways(patterns.clone(), design.clone(), &mut memo))
-        .sum::<usize>();
+        .map(|design| {
+            let ways = design_ways(&trie, &design, &mut memo) as u64;
+            (design, ways)
+        })
+        .collect()
+}
+
+// Prefix tree of the towel patterns, so we can find all patterns matching
+// the start of a design in a single scan
+#[derive(Default)]
+struct Trie {
+    children: HashMap<char, Trie>,
+    // Whether a pattern ends at this node
+    is_pattern: bool,
+}
+
+impl Trie {
+    fn new(patterns: &HashSet<String>) -> Self {
+        let mut trie = Self::default();
+        for pattern in patterns {
+            trie.insert(pattern);
+        }
+
+        trie
+    }
+
+    fn insert(&mut self, pattern: &str) {
+        let mut node = self;
+        for c in pattern.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        node.is_pattern = true;
+    }
+
+    // Return the lengths of all patterns that match a prefix of s
+    fn match_prefixes(&self, s: &str) -> Vec<usize> {
+        let mut lengths = Vec::new();
+        let mut node = self;
+        for (i, c) in s.chars().enumerate() {
+            let Some(child) = node.children.get(&c) else {
+                break;
+            };
+
+            node = child;
+            if node.is_pattern {
+                lengths.push(i + 1);
+            }
+        }
 
-    Some(ways as u64)
+        lengths
+    }
 }
 
-fn design_ways(
+fn design_ways(trie: &Trie, design: &str, memo: &mut HashMap<String, usize>) -> usize {
+    // base cases
+    if design.is_empty() {
+        return 1;
+    }
+    if let Some(cached) = memo.get(design) {
+        return *cached;
+    }
+
+    // Try to match the start of the design
+    let ways = trie
+        .match_prefixes(design)
+        .into_iter()
+        .map(|i| design_ways(trie, &design[i..], memo))
+        .sum();
+
+    memo.insert(design.to_string(), ways);
+    ways
+}
+
+// Original implementation scanning every prefix of the design.
+// Kept to cross-check the trie-based implementation in tests.
+#[cfg(test)]
+fn design_ways_naive(
     patterns: HashSet<String>,
     design: String,
     memo: &mut HashMap<String, usize>,
@@ -90,7 +171,7 @@ fn design_ways(
     let mut ways = 0;
     for i in 1..=design.len() {
         if patterns.contains(&design[..i]) {
-            ways += design_ways(patterns.clone(), design[i..].to_string(), memo);
+            ways += design_ways_naive(patterns.clone(), design[i..].to_string(), memo);
         }
     }
 
@@ -113,4 +194,55 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(16));
     }
+
+    #[test]
+    fn test_ways_per_design() {
+        let ways = ways_per_design(&advent_of_code::template::read_file("examples", DAY));
+
+        assert_eq!(ways.len(), 8);
+        // gbbr can be made 4 different ways
+        assert_eq!(ways[2], ("gbbr".to_string(), 4));
+        assert_eq!(ways[3], ("rrbgbr".to_string(), 6));
+        // ubwu is impossible
+        assert_eq!(ways[4], ("ubwu".to_string(), 0));
+
+        assert_eq!(ways.iter().map(|(_, ways)| ways).sum::<u64>(), 16);
+    }
+
+    #[test]
+    fn test_trie_match_prefixes() {
+        let patterns = HashSet::from_iter(["r", "rb", "rbg", "b"].map(ToString::to_string));
+        let trie = Trie::new(&patterns);
+
+        assert_eq!(trie.match_prefixes("rbgw"), vec![1, 2, 3]);
+        assert_eq!(trie.match_prefixes("rw"), vec![1]);
+        assert_eq!(trie.match_prefixes("w"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_design_ways_trie_matches_naive() {
+        let (patterns, designs) = parse(&advent_of_code::template::read_file("examples", DAY));
+        let trie = Trie::new(&patterns);
+        for design in designs {
+            assert_eq!(
+                design_ways(&trie, &design, &mut HashMap::new()),
+                design_ways_naive(patterns.clone(), design.clone(), &mut HashMap::new())
+            );
+        }
+
+        // Larger synthetic set: every pattern of length 1 to 3 over "wub", plus "r" and "gg"
+        let mut patterns: HashSet<String> = HashSet::from(["r".to_string(), "gg".to_string()]);
+        for len in 1..=3 {
+            for pattern in (0..len).map(|_| "wub".chars()).multi_cartesian_product() {
+                patterns.insert(pattern.into_iter().collect());
+            }
+        }
+        let trie = Trie::new(&patterns);
+        for design in ["wubwubwubrgguuw", "bbbbbbbbbbbbbbbbbbbb", "rgwrgw", "wugbu"] {
+            assert_eq!(
+                design_ways(&trie, design, &mut HashMap::new()),
+                design_ways_naive(patterns.clone(), design.to_string(), &mut HashMap::new())
+            );
+        }
+    }
 }
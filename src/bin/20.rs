@@ -1,6 +1,6 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::HashMap;
 
-use advent_of_code::{parse_maze, Coord, Dimensions, Maze};
+use advent_of_code::{bfs_distances, manhattan_ball, parse_maze_with_dimensions, Coord, Maze};
 
 advent_of_code::solution!(20);
 
@@ -10,8 +10,15 @@ pub fn part_one(input: &str) -> Option<u32> {
 
 // Threshold = number of picoseconds that must be saved in order to count the cheat
 fn part_one_inner(input: &str, threshold: u32) -> Option<u32> {
-    let maze = parse_maze(input);
-    let dimensions = Dimensions::from_input(input);
+    part_one_with_options(input, threshold, false)
+}
+
+// If require_track_start is set, the first step of the cheat must go into a wall, so that
+// the cheat actually passes through a wall. Otherwise the first step may also be on the track.
+// This only matters for cheats which don't save any time, since a cheat whose first step is
+// on the track is also a path without cheating.
+fn part_one_with_options(input: &str, threshold: u32, require_track_start: bool) -> Option<u32> {
+    let (maze, dimensions) = parse_maze_with_dimensions(input);
 
     // Find distance from start and end for each non-wall square
     let distance_from_start = distance_from_node(&maze, maze.start);
@@ -26,6 +33,11 @@ fn part_one_inner(input: &str, threshold: u32) -> Option<u32> {
     // total length of path = distance_from_start[node] + 2 + distance_from_end[cheat_end]
     for node in distance_from_start.keys().copied() {
         for cheat_start in dimensions.get_neighbors(&node) {
+            if require_track_start && !maze.walls.contains(&cheat_start) {
+                // Cheat must pass through a wall
+                continue;
+            }
+
             for cheat_end in dimensions.get_neighbors(&cheat_start) {
                 if maze.walls.contains(&cheat_end) {
                     // Must get back on the track at the end of the cheat
@@ -55,54 +67,31 @@ fn part_one_inner(input: &str, threshold: u32) -> Option<u32> {
     Some(count)
 }
 
-// Find the shortest distance from node to each other node in the maze
-fn distance_from_node(maze: &Maze, node: Coord) -> HashMap<Coord, u32> {
-    // BFS state object
-    #[derive(Clone, Debug)]
-    struct State {
-        pos: Coord,
-        path: HashSet<Coord>,
-    }
+// Number of picoseconds saved by cheating from start to end (negative if it loses time),
+// or None if either end of the cheat is not on the track
+#[cfg(test)]
+fn savings_for_cheat(input: &str, start: Coord, end: Coord) -> Option<i64> {
+    let maze = advent_of_code::parse_maze(input);
 
-    let mut distances = HashMap::new();
-    let start_state = State {
-        pos: node,
-        path: HashSet::new(),
-    };
-
-    // BFS
-    let mut queue = VecDeque::from_iter([start_state]);
-    while let Some(State { pos, path }) = queue.pop_front() {
-        // insert if not present
-        distances.entry(pos).or_insert_with(|| path.len() as u32);
-
-        let new_path = {
-            let mut new_path = path.clone();
-            new_path.insert(pos);
-            new_path
-        };
+    let distance_from_start = distance_from_node(&maze, maze.start);
+    let distance_from_end = distance_from_node(&maze, maze.end);
 
-        for neighbor in pos.get_neighbors() {
-            if maze.walls.contains(&neighbor) {
-                // hit a wall
-                continue;
-            }
+    let shortest_path_without_cheating = *distance_from_start.get(&maze.end).unwrap() as i64;
 
-            if path.contains(&neighbor) {
-                // the path already contains this node, but the shortest path
-                // should not contain the same node twice
-                continue;
-            }
+    let dist = *distance_from_start.get(&start)? as i64
+        + start.manhattan_dist(&end) as i64
+        + *distance_from_end.get(&end)? as i64;
 
-            // Add to the queue
-            queue.push_back(State {
-                pos: neighbor,
-                path: new_path.clone(),
-            });
-        }
-    }
+    Some(shortest_path_without_cheating - dist)
+}
 
-    distances
+// Find the shortest distance from node to each other node in the maze
+fn distance_from_node(maze: &Maze, node: Coord) -> HashMap<Coord, u32> {
+    bfs_distances(node, |pos| {
+        pos.get_neighbors()
+            .filter(|neighbor| !maze.walls.contains(neighbor))
+            .collect()
+    })
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
@@ -111,8 +100,7 @@ pub fn part_two(input: &str) -> Option<u32> {
 
 // Threshold = number of picoseconds that must be saved in order to count the cheat
 fn part_two_inner(input: &str, threshold: u32) -> Option<u32> {
-    let maze = parse_maze(input);
-    let dimensions = Dimensions::from_input(input);
+    let (maze, dimensions) = parse_maze_with_dimensions(input);
 
     // Find distance from start and end for each non-wall square
     let distance_from_start = distance_from_node(&maze, maze.start);
@@ -128,31 +116,26 @@ fn part_two_inner(input: &str, threshold: u32) -> Option<u32> {
     // For each possible cheat_start, check all possible cheat_ends within cheat_length 20
     // where cheat_length = manhattan_distance(cheat_start, cheat_end).
     for cheat_start in distance_from_start.keys() {
-        for dx in -20..=20_i64 {
-            let remaining_cheat_len = 20 - dx.abs();
-            for dy in -remaining_cheat_len..=remaining_cheat_len {
-                let cheat_len = dx.abs() + dy.abs();
-                let cheat_end = cheat_start.step(dx, dy);
-                if !dimensions.in_bounds(&cheat_end) {
-                    // cheat end is not in the maze
-                    continue;
-                }
-                if maze.walls.contains(&cheat_end) {
-                    // cheat must end on the track
-                    continue;
-                }
+        for (cheat_end, cheat_len) in manhattan_ball(*cheat_start, 20) {
+            if !dimensions.in_bounds(&cheat_end) {
+                // cheat end is not in the maze
+                continue;
+            }
+            if maze.walls.contains(&cheat_end) {
+                // cheat must end on the track
+                continue;
+            }
 
-                let dist = distance_from_start
-                    .get(cheat_start)
-                    .expect("cheat_start not in distance_from_start")
-                    + cheat_len as u32
-                    + distance_from_end
-                        .get(&cheat_end)
-                        .expect("cheat_end not in distance_from_end");
+            let dist = distance_from_start
+                .get(cheat_start)
+                .expect("cheat_start not in distance_from_start")
+                + cheat_len as u32
+                + distance_from_end
+                    .get(&cheat_end)
+                    .expect("cheat_end not in distance_from_end");
 
-                if dist <= shortest_path_without_cheating.saturating_sub(threshold) {
-                    count += 1;
-                }
+            if dist <= shortest_path_without_cheating.saturating_sub(threshold) {
+                count += 1;
             }
         }
     }
@@ -190,6 +173,52 @@ mod tests {
         assert_eq!(run_with_threshold(2), Some(44));
     }
 
+    #[test]
+    fn test_require_track_start() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+
+        // Any cheat which saves time must pass through a wall
+        for threshold in [1, 2, 64] {
+            assert_eq!(
+                part_one_with_options(&input, threshold, true),
+                part_one_with_options(&input, threshold, false)
+            );
+        }
+
+        // Counting cheats which save no time, without the flag we also count the 83 ways
+        // to take two steps along the track (which has 85 cells)
+        assert_eq!(part_one_with_options(&input, 0, true), Some(77));
+        assert_eq!(part_one_with_options(&input, 0, false), Some(77 + 83));
+    }
+
+    #[test]
+    fn test_savings_for_cheat() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+
+        // First cheat from the example, through the wall in the second row
+        // (with (0, 0) at the bottom left)
+        assert_eq!(
+            savings_for_cheat(&input, Coord::new(7, 13), Coord::new(9, 13)),
+            Some(12)
+        );
+
+        // Same cheat in reverse loses time
+        assert_eq!(
+            savings_for_cheat(&input, Coord::new(9, 13), Coord::new(7, 13)),
+            Some(-16)
+        );
+
+        // Cheat can't start or end in a wall
+        assert_eq!(
+            savings_for_cheat(&input, Coord::new(8, 13), Coord::new(9, 13)),
+            None
+        );
+        assert_eq!(
+            savings_for_cheat(&input, Coord::new(7, 13), Coord::new(8, 13)),
+            None
+        );
+    }
+
     #[test]
     fn test_part_two() {
         let run_with_threshold = |threshold| {
@@ -7,28 +7,55 @@ use advent_of_code::{Coord, Direction};
 advent_of_code::solution!(21);
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let codes = input.lines().collect::<Vec<_>>();
-
-    let mut sum = 0;
-    for code in codes {
-        // numeric keypad
-        let path1 = find_shortest_path_for_sequence(&NUMERIC_KEYPAD, code);
-        // directional keypad 1
-        let path2 = find_shortest_path_for_sequence(&DIRECTIONAL_KEYPAD, &path1);
-        // directional keypad 2
-        let path3 = find_shortest_path_for_sequence(&DIRECTIONAL_KEYPAD, &path2);
-
-        let shortest_sequence_len = path3.len() as u32;
-        let numeric_part_of_code: u32 = code
-            .split_at(code.len() - 1)
-            .0
-            .parse()
-            .expect("Failed to parse numeric part of code");
-
-        sum += shortest_sequence_len * numeric_part_of_code;
-    }
+    let sum: u64 = complexities(input, 2, PathStrategy::default())
+        .into_iter()
+        .map(|(_, shortest_sequence_len, numeric_part_of_code)| {
+            shortest_sequence_len * numeric_part_of_code
+        })
+        .sum();
+
+    Some(sum as u32)
+}
 
-    Some(sum)
+// For each code, return (code, shortest sequence length, numeric part of code)
+// with the given number of directional keypad robots between us and the numeric keypad
+fn complexities(input: &str, num_robots: usize, strategy: PathStrategy) -> Vec<(String, u64, u64)> {
+    input
+        .lines()
+        .map(|code| {
+            // numeric keypad
+            let path1 = find_shortest_path_for_sequence(&NUMERIC_KEYPAD, code, strategy);
+
+            let shortest_sequence_len =
+                shortest_path_len_for_sequence_with_n_robots(num_robots, path1, strategy);
+            let numeric_part_of_code: u64 = code
+                .split_at(code.len() - 1)
+                .0
+                .parse()
+                .expect("Failed to parse numeric part of code");
+
+            (
+                code.to_string(),
+                shortest_sequence_len,
+                numeric_part_of_code,
+            )
+        })
+        .collect()
+}
+
+// Order to take the horizontal and vertical segments of a path in, when both are
+// possible without crossing the gap
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+enum PathStrategy {
+    // Left first, then up/down, then right (optimal, see find_shortest_path_inner)
+    #[default]
+    LeftVerticalRight,
+    // Always left/right before up/down
+    #[cfg(test)]
+    HorizontalFirst,
+    // Always up/down before left/right
+    #[cfg(test)]
+    VerticalFirst,
 }
 
 struct Keypad {
@@ -92,27 +119,36 @@ lazy_static! {
     ]),
     1,
     );
-
-    static ref DIRECTIONS: HashMap<char, Direction> = HashMap::from([
-        ('<', Direction::Left),
-        ('>', Direction::Right),
-        ('^', Direction::Up),
-        ('v', Direction::Down),
-    ]);
 }
 
 // Return shortest sequence to type out a sequence on the given keypad using a directional keypad
-fn find_shortest_path_for_sequence(keypad: &'static Keypad, sequence: &str) -> String {
+fn find_shortest_path_for_sequence(
+    keypad: &'static Keypad,
+    sequence: &str,
+    strategy: PathStrategy,
+) -> String {
+    // Robots always start at the A
+    find_shortest_path_for_sequence_from(keypad, 'A', sequence, strategy)
+}
+
+// Same as find_shortest_path_for_sequence, but starting from the given button instead of A,
+// e.g. to continue a sequence which was partially typed already
+fn find_shortest_path_for_sequence_from(
+    keypad: &'static Keypad,
+    start: char,
+    sequence: &str,
+    strategy: PathStrategy,
+) -> String {
     if sequence.is_empty() {
         return "".to_string();
     }
 
-    let mut curr = 'A'; // always start at the A
+    let mut curr = start;
 
     let mut output = String::new();
     for c in sequence.chars() {
         // navigate to the next button
-        let path = find_shortest_path(keypad, curr, c);
+        let path = find_shortest_path(keypad, curr, c, strategy);
         output.push_str(&path);
 
         curr = c;
@@ -124,13 +160,26 @@ fn find_shortest_path_for_sequence(keypad: &'static Keypad, sequence: &str) -> S
 // Shortest path from start to pressing end
 // gap_y is the y-coordinate of the gap
 #[cached]
-fn find_shortest_path(keypad: &'static Keypad, start: char, end: char) -> String {
+fn find_shortest_path(
+    keypad: &'static Keypad,
+    start: char,
+    end: char,
+    strategy: PathStrategy,
+) -> String {
     // add the button press 'A'
-    format!("{}A", find_shortest_path_inner(keypad, start, end))
+    format!(
+        "{}A",
+        find_shortest_path_inner(keypad, start, end, strategy)
+    )
 }
 
 // Find shortest path from start to end (excluding the button press)
-fn find_shortest_path_inner(keypad: &'static Keypad, start: char, end: char) -> String {
+fn find_shortest_path_inner(
+    keypad: &'static Keypad,
+    start: char,
+    end: char,
+    strategy: PathStrategy,
+) -> String {
     if start == end {
         // we are already in the right position
         return "".to_string();
@@ -165,7 +214,14 @@ fn find_shortest_path_inner(keypad: &'static Keypad, start: char, end: char) ->
     // In other cases, we should always prefer going left first, then up/down, then right.
     // This is based on distance from the button A - going to the furthest button first
     // shortens the overall distance since we always have to end with A.
-    if dx < 0 {
+    let horizontal_first = match strategy {
+        PathStrategy::LeftVerticalRight => dx < 0,
+        #[cfg(test)]
+        PathStrategy::HorizontalFirst => true,
+        #[cfg(test)]
+        PathStrategy::VerticalFirst => false,
+    };
+    if horizontal_first {
         format!("{dx_path}{dy_path}")
     } else {
         format!("{dy_path}{dx_path}")
@@ -200,7 +256,7 @@ fn translate(sequence: &str, keypad: &Keypad) -> String {
         if c == 'A' {
             output.push(*keypad.reverse_button_mapping.get(&curr).unwrap());
         } else {
-            curr = curr.step_in_direction(*DIRECTIONS.get(&c).unwrap());
+            curr = curr.step_in_direction(Direction::from_char(c).unwrap());
         }
     }
 
@@ -209,7 +265,11 @@ fn translate(sequence: &str, keypad: &Keypad) -> String {
 
 // Directional keypad robots only
 #[cached]
-fn shortest_path_len_for_sequence_with_n_robots(n: usize, sequence: String) -> u64 {
+fn shortest_path_len_for_sequence_with_n_robots(
+    n: usize,
+    sequence: String,
+    strategy: PathStrategy,
+) -> u64 {
     // base case
     if n == 0 {
         return sequence.len() as u64;
@@ -221,7 +281,8 @@ fn shortest_path_len_for_sequence_with_n_robots(n: usize, sequence: String) -> u
     for c in sequence.chars() {
         path_len += shortest_path_len_for_sequence_with_n_robots(
             n - 1,
-            find_shortest_path(&DIRECTIONAL_KEYPAD, curr, c),
+            find_shortest_path(&DIRECTIONAL_KEYPAD, curr, c, strategy),
+            strategy,
         );
         curr = c;
     }
@@ -230,23 +291,12 @@ fn shortest_path_len_for_sequence_with_n_robots(n: usize, sequence: String) -> u
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let codes = input.lines().collect::<Vec<_>>();
-
-    let mut sum = 0;
-
-    for code in codes {
-        // numeric keypad
-        let path1 = find_shortest_path_for_sequence(&NUMERIC_KEYPAD, code);
-
-        let shortest_sequence_len = shortest_path_len_for_sequence_with_n_robots(25, path1);
-        let numeric_part_of_code: u64 = code
-            .split_at(code.len() - 1)
-            .0
-            .parse()
-            .expect("Failed to parse numeric part of code");
-
-        sum += shortest_sequence_len * numeric_part_of_code;
-    }
+    let sum = complexities(input, 25, PathStrategy::default())
+        .into_iter()
+        .map(|(_, shortest_sequence_len, numeric_part_of_code)| {
+            shortest_sequence_len * numeric_part_of_code
+        })
+        .sum();
 
     Some(sum)
 }
@@ -257,18 +307,81 @@ mod tests {
 
     #[test]
     fn test_find_shortest_paths_numeric() {
-        assert_eq!(&find_shortest_path(&NUMERIC_KEYPAD, 'A', '0'), "<A");
-        assert_eq!(&find_shortest_path(&NUMERIC_KEYPAD, '0', '2'), "^A");
-        assert_eq!(&find_shortest_path(&NUMERIC_KEYPAD, '2', '9'), "^^>A");
-        assert_eq!(&find_shortest_path(&NUMERIC_KEYPAD, '9', 'A'), "vvvA");
+        assert_eq!(
+            &find_shortest_path(&NUMERIC_KEYPAD, 'A', '0', PathStrategy::default()),
+            "<A"
+        );
+        assert_eq!(
+            &find_shortest_path(&NUMERIC_KEYPAD, '0', '2', PathStrategy::default()),
+            "^A"
+        );
+        assert_eq!(
+            &find_shortest_path(&NUMERIC_KEYPAD, '2', '9', PathStrategy::default()),
+            "^^>A"
+        );
+        assert_eq!(
+            &find_shortest_path(&NUMERIC_KEYPAD, '9', 'A', PathStrategy::default()),
+            "vvvA"
+        );
     }
 
     #[test]
     fn test_find_shortest_sequence_numeric() {
-        let sequence = find_shortest_path_for_sequence(&NUMERIC_KEYPAD, "029A");
+        let sequence =
+            find_shortest_path_for_sequence(&NUMERIC_KEYPAD, "029A", PathStrategy::default());
         assert_eq!(sequence, "<A^A^^>AvvvA".to_string());
     }
 
+    #[test]
+    fn test_find_shortest_sequence_from() {
+        let strategy = PathStrategy::default();
+        assert_eq!(
+            find_shortest_path_for_sequence_from(&NUMERIC_KEYPAD, '0', "2", strategy),
+            "^A"
+        );
+        assert_eq!(
+            find_shortest_path_for_sequence_from(&NUMERIC_KEYPAD, 'A', "029A", strategy),
+            find_shortest_path_for_sequence(&NUMERIC_KEYPAD, "029A", strategy)
+        );
+
+        // Typing a sequence in two parts is the same as typing it all at once
+        let first = find_shortest_path_for_sequence(&NUMERIC_KEYPAD, "02", strategy);
+        let rest = find_shortest_path_for_sequence_from(&NUMERIC_KEYPAD, '2', "9A", strategy);
+        assert_eq!(first + &rest, "<A^A^^>AvvvA");
+    }
+
+    #[test]
+    fn test_complexities() {
+        let complexities = complexities(
+            &advent_of_code::template::read_file("examples", DAY),
+            2,
+            PathStrategy::default(),
+        );
+        assert_eq!(complexities[0], ("029A".to_string(), 68, 29));
+    }
+
+    #[test]
+    fn test_path_strategies() {
+        let total_len = |strategy, num_robots| -> u64 {
+            complexities("379A", num_robots, strategy)
+                .into_iter()
+                .map(|(_, shortest_sequence_len, _)| shortest_sequence_len)
+                .sum()
+        };
+
+        // 379A from the example
+        // With only a couple of robots, the ordering doesn't always matter yet
+        assert_eq!(total_len(PathStrategy::default(), 2), 64);
+        assert_eq!(total_len(PathStrategy::HorizontalFirst, 2), 64);
+        assert_eq!(total_len(PathStrategy::VerticalFirst, 2), 68);
+
+        // But the differences compound with more robots
+        let default_len = total_len(PathStrategy::default(), 10);
+        assert_eq!(default_len, 90210);
+        assert!(default_len < total_len(PathStrategy::HorizontalFirst, 10));
+        assert!(default_len < total_len(PathStrategy::VerticalFirst, 10));
+    }
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
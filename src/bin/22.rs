@@ -43,14 +43,76 @@ fn next(num: u64) -> u64 {
     (num ^ (num << 11)) % 16777216
 }
 
+// Brent's cycle detection algorithm for the sequence start, f(start), f(f(start)), ...
+// Returns (index of the first element in the cycle, length of the cycle)
+#[cfg(test)]
+fn find_cycle<T: Copy + Eq>(start: T, f: impl Fn(T) -> T) -> (usize, usize) {
+    // Find the cycle length by searching in successive powers of two
+    let mut power = 1;
+    let mut cycle_length = 1;
+    let mut tortoise = start;
+    let mut hare = f(start);
+    while tortoise != hare {
+        if power == cycle_length {
+            tortoise = hare;
+            power *= 2;
+            cycle_length = 0;
+        }
+        hare = f(hare);
+        cycle_length += 1;
+    }
+
+    // Find the start of the cycle, with the hare cycle_length steps ahead of the tortoise
+    let mut cycle_start = 0;
+    tortoise = start;
+    hare = start;
+    for _ in 0..cycle_length {
+        hare = f(hare);
+    }
+    while tortoise != hare {
+        tortoise = f(tortoise);
+        hare = f(hare);
+        cycle_start += 1;
+    }
+
+    (cycle_start, cycle_length)
+}
+
+// Period of the sequence of secret numbers starting from the seed
+// Since mixing and pruning is a bijection on numbers below 2^24, every sequence
+// eventually cycles, and each buyer's 2000 secret numbers are far shorter than a cycle
+#[cfg(test)]
+fn cycle_length(seed: u64) -> usize {
+    find_cycle(seed, next).1
+}
+
 pub fn part_two(input: &str) -> Option<u32> {
     let secret_numbers = parse(input);
+    let (_, bananas_for_sequence) = simulate(&secret_numbers);
+
+    bananas_for_sequence.into_values().max()
+}
+
+// Compute the answers to both parts, running each buyer's sequence only once
+#[cfg(test)]
+fn solve_both(input: &str) -> (u64, u32) {
+    let secret_numbers = parse(input);
+    let (sum, bananas_for_sequence) = simulate(&secret_numbers);
+
+    (sum, bananas_for_sequence.into_values().max().unwrap_or(0))
+}
+
+// Run 2000 iterations for each buyer, returning the sum of the final secret numbers
+// and the number of bananas for each sequence of changes
+fn simulate(secret_numbers: &[u64]) -> (u64, HashMap<Sequence, u32>) {
+    let mut sum = 0;
 
     // Brute force - go through all the secret number values
     // and store the number of bananas for each sequence of changes
     let mut bananas_for_sequence = HashMap::new();
 
-    for mut secret_number in secret_numbers {
+    for &secret_number in secret_numbers {
+        let mut secret_number = secret_number;
         let mut visited_sequences = HashSet::new();
         let mut diffs = Diffs::new();
         for _ in 0..2000 {
@@ -74,13 +136,17 @@ pub fn part_two(input: &str) -> Option<u32> {
 
             secret_number = new_secret_number;
         }
+
+        sum += secret_number;
     }
 
-    bananas_for_sequence.into_values().max()
+    (sum, bananas_for_sequence)
 }
 
 // A sequence of 4 price changes
-// Treat as a ring buffer to simplify push & shift operations
+type Sequence = (i8, i8, i8, i8);
+
+// Treat the last 4 price changes as a ring buffer to simplify push & shift operations
 struct Diffs {
     diffs: [i8; 4],
     ptr: usize,
@@ -94,7 +160,7 @@ impl Diffs {
         }
     }
 
-    fn get(&self) -> Option<(i8, i8, i8, i8)> {
+    fn get(&self) -> Option<Sequence> {
         if self.ptr < 4 {
             // We haven't seen 4 price changes yet
             return None;
@@ -153,4 +219,38 @@ mod tests {
         ));
         assert_eq!(result, Some(23));
     }
+
+    #[test]
+    fn test_solve_both() {
+        let input_1 = advent_of_code::template::read_file_part("examples", DAY, 1);
+        let input_2 = advent_of_code::template::read_file_part("examples", DAY, 2);
+
+        assert_eq!(solve_both(&input_1).0, 37327623);
+        assert_eq!(solve_both(&input_2).1, 23);
+
+        // Should agree with the separate parts
+        assert_eq!(Some(solve_both(&input_1).0), part_one(&input_1));
+        assert_eq!(Some(solve_both(&input_2).1), part_two(&input_2));
+    }
+
+    #[test]
+    fn test_cycle_length() {
+        // 0 is a fixed point
+        assert_eq!(cycle_length(0), 1);
+
+        // Brute force - since next is a bijection, the sequence returns to the seed
+        let seed = 123;
+        let mut secret_number = next(seed);
+        let mut brute_force_cycle_length = 1;
+        while secret_number != seed {
+            secret_number = next(secret_number);
+            brute_force_cycle_length += 1;
+        }
+        assert_eq!(cycle_length(seed), brute_force_cycle_length);
+        // Every nonzero number below 2^24 is in the same cycle
+        assert_eq!(brute_force_cycle_length, 16777215);
+
+        // Sequence with a tail before the cycle: 0, 1, 2, 3, 4, 5, 5, ...
+        assert_eq!(find_cycle(0_u64, |n| (n + 1).min(5)), (5, 1));
+    }
 }
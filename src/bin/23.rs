@@ -21,25 +21,34 @@ fn parse_edge(input: &str) -> (String, String) {
 pub fn part_one(input: &str) -> Option<u32> {
     let graph = parse(input);
 
+    // Only count sets containing a node that starts with t
+    let count = triangles(&graph)
+        .into_iter()
+        .filter(|(n1, n2, n3)| [n1, n2, n3].iter().any(|n| n.starts_with("t")))
+        .count();
+
+    Some(count as u32)
+}
+
+// Find all sets of three interconnected nodes, with the nodes in each set sorted
+fn triangles(graph: &UndirectedGraph) -> HashSet<(String, String, String)> {
     let mut three_interconnected = HashSet::new();
     for (n1, neighbors1) in graph.edges.iter() {
-        // Only check from nodes that start with t
-        if !n1.starts_with("t") {
-            continue;
-        }
-
         for n2 in neighbors1 {
             // Any other node that is a neighbor of both n1 and n2
             // creates an interconnected component
-            for n3 in graph.get_neighbors(n2) {
-                if neighbors1.contains(n3) {
-                    three_interconnected.insert(sort_nodes(n1, n2, n3));
-                }
+            for n3 in graph.common_neighbors(n1, n2) {
+                three_interconnected.insert(sort_nodes(n1, n2, &n3));
             }
         }
     }
 
-    Some(three_interconnected.len() as u32)
+    three_interconnected
+}
+
+#[cfg(test)]
+fn count_triangles(graph: &UndirectedGraph) -> u32 {
+    triangles(graph).len() as u32
 }
 
 // Sort alphabetically
@@ -87,6 +96,14 @@ impl UndirectedGraph {
             .unwrap_or_else(|| panic!("Node {node} not in graph"))
     }
 
+    // Nodes which are neighbors of both a and b
+    fn common_neighbors(&self, a: &str, b: &str) -> HashSet<String> {
+        self.get_neighbors(a)
+            .intersection(self.get_neighbors(b))
+            .cloned()
+            .collect()
+    }
+
     fn nodes(&self) -> impl Iterator<Item = String> + '_ {
         self.edges.keys().cloned()
     }
@@ -142,13 +159,12 @@ pub fn part_two(input: &str) -> Option<String> {
     let largest_interconnected_set =
         find_largest_interconnected_set(&graph, HashSet::new(), graph.nodes().collect());
 
-    Some(get_password(largest_interconnected_set))
+    Some(clique_password(&largest_interconnected_set))
 }
 
-fn get_password(set: HashSet<String>) -> String {
-    let mut v = Vec::from_iter(set);
-    v.sort();
-    v.join(",")
+// Serialize a clique by sorting the nodes alphabetically and joining with commas
+fn clique_password(clique: &HashSet<String>) -> String {
+    clique.iter().sorted().join(",")
 }
 
 #[cfg(test)]
@@ -161,6 +177,33 @@ mod tests {
         assert_eq!(result, Some(7));
     }
 
+    #[test]
+    fn test_count_triangles() {
+        let graph = parse(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(count_triangles(&graph), 12);
+    }
+
+    #[test]
+    fn test_common_neighbors() {
+        let graph = parse(&advent_of_code::template::read_file("examples", DAY));
+
+        let expected = HashSet::from(["ka", "ta"].map(ToString::to_string));
+        assert_eq!(graph.common_neighbors("co", "de"), expected);
+        assert_eq!(graph.common_neighbors("de", "co"), expected);
+
+        // Connected, but not part of any triangle
+        assert!(graph.common_neighbors("kh", "tc").is_empty());
+    }
+
+    #[test]
+    fn test_clique_password() {
+        let clique1 = HashSet::from_iter(["ta", "co", "ka", "de"].map(ToString::to_string));
+        let clique2 = HashSet::from_iter(["de", "ka", "ta", "co"].map(ToString::to_string));
+
+        assert_eq!(clique_password(&clique1), "co,de,ka,ta");
+        assert_eq!(clique_password(&clique1), clique_password(&clique2));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
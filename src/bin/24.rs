@@ -26,16 +26,40 @@ impl Circuit {
     }
 
     fn solve_for(&self, name: &str) -> bool {
-        if let Some(out) = self.values.get(name) {
-            return *out;
+        self.get_wire(name)
+            .unwrap_or_else(|| panic!("Could not solve for wire {name}"))
+    }
+
+    // Value of any wire, evaluating gates as needed.
+    // Returns None if the wire (or one of its inputs) doesn't exist, or if it depends on
+    // itself, which can happen after swapping gate outputs.
+    fn get_wire(&self, name: &str) -> Option<bool> {
+        self.get_wire_evaluating(name, &mut HashSet::new())
+    }
+
+    // evaluating holds the wires currently being evaluated, to detect cycles
+    fn get_wire_evaluating(&self, name: &str, evaluating: &mut HashSet<String>) -> Option<bool> {
+        if let Some(value) = self.values.get(name) {
+            return Some(*value);
         }
 
-        let gate = self.gates.get(name).unwrap();
+        let gate = self.gates.get(name)?;
+        if !evaluating.insert(name.to_string()) {
+            // cycle
+            return None;
+        }
+
+        let in1 = self.get_wire_evaluating(&gate.in1, evaluating);
+        let in2 = self.get_wire_evaluating(&gate.in2, evaluating);
+        evaluating.remove(name);
 
-        let in1 = self.solve_for(&gate.in1);
-        let in2 = self.solve_for(&gate.in2);
+        Some(gate.get_output(in1?, in2?))
+    }
 
-        gate.get_output(in1, in2)
+    // Override the value of a wire
+    #[cfg(test)]
+    fn set_wire(&mut self, name: &str, value: bool) {
+        self.values.insert(name.to_string(), value);
     }
 
     // This does some redundant computations but is fast enough for part 1
@@ -227,6 +251,83 @@ impl Gates {
             .or_else(|| self.by_op_and_inputs.get(&k2))
             .and_then(|i| self.gates.get(*i))
     }
+
+    // Number of gates of each op type
+    // A correct n-bit ripple-carry adder has 2n - 1 XOR gates, 2n - 1 AND gates and n - 1 OR gates
+    #[cfg(test)]
+    fn op_counts(&self) -> HashMap<Op, usize> {
+        self.gates.iter().map(|gate| gate.op.clone()).counts()
+    }
+}
+
+// Circuit with the x and y input wires set from the lowest num_bits bits of the given integers.
+// Any other input wires of the gates are set to 0.
+#[cfg(test)]
+fn circuit_with_inputs(gates: &Gates, x: u64, y: u64, num_bits: usize) -> Circuit {
+    let bit = |value: u64, i: usize| i < num_bits && (value >> i) & 1 == 1;
+
+    let mut initial_values = HashMap::new();
+    for input in gates.gates.iter().flat_map(|gate| [&gate.in1, &gate.in2]) {
+        let Ok(i) = input[1..].parse::<usize>() else {
+            continue;
+        };
+
+        if input.starts_with('x') {
+            initial_values.insert(input.clone(), bit(x, i));
+        } else if input.starts_with('y') {
+            initial_values.insert(input.clone(), bit(y, i));
+        }
+    }
+
+    Circuit::new(initial_values, gates.gates.clone())
+}
+
+// Set the x and y input wires from the given integers and compute the resulting z value
+#[cfg(test)]
+fn compute_z(gates: &Gates, x: u64, y: u64, num_bits: usize) -> u64 {
+    circuit_with_inputs(gates, x, y, num_bits).get_z_value()
+}
+
+// For each z output bit, whether it matched the bit of x + y for every one of the test cases.
+// Bits which can't be evaluated because they depend on themselves are incorrect.
+// Uses pseudo-random x and y so the results are the same each run.
+#[cfg(test)]
+fn z_correctness(gates: &Gates, test_cases: usize) -> Vec<bool> {
+    let num_bits = gates
+        .gates
+        .iter()
+        .flat_map(|gate| [&gate.in1, &gate.in2])
+        .filter(|input| input.starts_with('x'))
+        .unique()
+        .count();
+    let num_z_bits = gates
+        .gates
+        .iter()
+        .filter(|gate| gate.out.starts_with('z'))
+        .count();
+
+    // Simple linear congruential generator
+    let mut state: u64 = 12345;
+    let mut random_input = || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (state >> 16) & ((1 << num_bits) - 1)
+    };
+
+    let mut correct = vec![true; num_z_bits];
+    for _ in 0..test_cases {
+        let x = random_input();
+        let y = random_input();
+        let circuit = circuit_with_inputs(gates, x, y, num_bits);
+
+        for (i, bit_correct) in correct.iter_mut().enumerate() {
+            let expected = ((x + y) >> i) & 1 == 1;
+            if circuit.get_wire(&format!("z{i:0>2}")) != Some(expected) {
+                *bit_correct = false;
+            }
+        }
+    }
+
+    correct
 }
 
 impl Debug for Gates {
@@ -387,9 +488,109 @@ fn sort_and_join_swaps(swaps: HashSet<String>) -> String {
 mod tests {
     use super::*;
 
+    // A correct 3-bit ripple-carry adder
+    const ADDER_3_BITS: &str = "\
+x00: 0
+x01: 0
+x02: 0
+y00: 0
+y01: 0
+y02: 0
+
+x00 XOR y00 -> z00
+x00 AND y00 -> c01
+x01 XOR y01 -> s01
+s01 XOR c01 -> z01
+x01 AND y01 -> a01
+s01 AND c01 -> b01
+a01 OR b01 -> c02
+x02 XOR y02 -> s02
+s02 XOR c02 -> z02
+x02 AND y02 -> a02
+s02 AND c02 -> b02
+a02 OR b02 -> z03";
+
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2024));
     }
+
+    #[test]
+    fn test_compute_z() {
+        let gates = parse_gates(ADDER_3_BITS);
+
+        assert_eq!(compute_z(&gates, 3, 5, 3), 8);
+
+        for x in 0..8 {
+            for y in 0..8 {
+                assert_eq!(compute_z(&gates, x, y, 3), x + y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_z_correctness() {
+        let mut gates = parse_gates(ADDER_3_BITS);
+        assert_eq!(z_correctness(&gates, 20), [true; 4]);
+
+        // Swapping the sum and carry in bit 2 breaks z02 and the carry into z03,
+        // but leaves the lower bits alone
+        gates.swap("z02", "b02");
+        assert_eq!(z_correctness(&gates, 20), [true, true, false, false]);
+    }
+
+    #[test]
+    fn test_z_correctness_with_cycle() {
+        let mut gates = parse_gates(ADDER_3_BITS);
+
+        // s01 becomes s01 XOR c01, so everything after it depends on itself,
+        // while z01 becomes x01 XOR y01 which ignores the carry
+        gates.swap("s01", "z01");
+        assert_eq!(z_correctness(&gates, 20), [true, false, false, false]);
+
+        let circuit = circuit_with_inputs(&gates, 0, 0, 3);
+        assert_eq!(circuit.get_wire("s01"), None);
+        assert_eq!(circuit.get_wire("z03"), None);
+        assert_eq!(circuit.get_wire("z01"), Some(false));
+    }
+
+    #[test]
+    fn test_compute_z_fewer_bits() {
+        // Inputs above num_bits are 0
+        let gates = parse_gates(ADDER_3_BITS);
+        assert_eq!(compute_z(&gates, 0b111, 0b001, 2), 0b011 + 0b001);
+        assert_eq!(compute_z(&gates, 5, 6, 0), 0);
+    }
+
+    #[test]
+    fn test_op_counts() {
+        let gates = parse_gates(ADDER_3_BITS);
+        assert_eq!(
+            gates.op_counts(),
+            HashMap::from([(Op::XOR, 5), (Op::AND, 5), (Op::OR, 2)])
+        );
+
+        let gates = parse_gates(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(gates.op_counts().values().sum::<usize>(), gates.gates.len());
+    }
+
+    #[test]
+    fn test_get_and_set_wire() {
+        let mut circuit = parse(ADDER_3_BITS);
+
+        assert_eq!(circuit.get_wire("x00"), Some(false));
+        assert_eq!(circuit.get_wire("z00"), Some(false));
+        assert_eq!(circuit.get_wire("c01"), Some(false));
+        assert_eq!(circuit.get_wire("abc"), None);
+
+        circuit.set_wire("x00", true);
+        assert_eq!(circuit.get_wire("z00"), Some(true));
+        assert_eq!(circuit.get_wire("c01"), Some(false));
+
+        circuit.set_wire("y00", true);
+        assert_eq!(circuit.get_wire("z00"), Some(false));
+        assert_eq!(circuit.get_wire("c01"), Some(true));
+        assert_eq!(circuit.get_wire("z01"), Some(true));
+    }
 }
@@ -1,6 +1,9 @@
 use std::{
-    collections::HashSet,
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
     fmt::{Debug, Display},
+    hash::Hash,
+    ops::{Add, Mul, Sub},
     str::FromStr,
 };
 
@@ -30,6 +33,14 @@ pub enum Direction {
 }
 
 impl Direction {
+    /// The four directions, in the same order as `to_index`: Up, Down, Left, Right.
+    pub const ALL: [Direction; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    /// Iterate over the four directions in the order of `Direction::ALL`.
+    pub fn all() -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter()
+    }
+
     pub fn to_dx_dy(self) -> (i64, i64) {
         match self {
             Self::Up => (0, 1),
@@ -56,6 +67,80 @@ impl Direction {
             Self::Right => Self::Up,
         }
     }
+
+    /// Parse a direction from `^`, `v`, `<` or `>`, returning None for any other char
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Self::Up),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    /// Inverse of `from_char`
+    pub fn to_char(self) -> char {
+        match self {
+            Self::Up => '^',
+            Self::Down => 'v',
+            Self::Left => '<',
+            Self::Right => '>',
+        }
+    }
+
+    /// Reverse direction, i.e. Up <-> Down and Left <-> Right
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// Alias for `opposite`
+    pub const fn turn_180(self) -> Self {
+        self.opposite()
+    }
+
+    /// Stable index in 0..4 so directions can key into a `[T; 4]`.
+    /// Up = 0, Down = 1, Left = 2, Right = 3.
+    pub fn to_index(self) -> usize {
+        match self {
+            Self::Up => 0,
+            Self::Down => 1,
+            Self::Left => 2,
+            Self::Right => 3,
+        }
+    }
+
+    /// Inverse of `to_index`, returning None if the index is out of range
+    pub fn from_index(i: usize) -> Option<Direction> {
+        match i {
+            0 => Some(Self::Up),
+            1 => Some(Self::Down),
+            2 => Some(Self::Left),
+            3 => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    /// Direction to step in to get from `from` to `to`,
+    /// or None if they are not exactly one cardinal step apart
+    pub fn between(from: &Coord, to: &Coord) -> Option<Direction> {
+        let delta = to.diff(from);
+        Self::all().find(|dir| dir.to_dx_dy() == delta)
+    }
+}
+
+/// A side of a 2D grid, using the coordinate system with (0,0) at bottom left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -96,8 +181,32 @@ impl Coord {
             .map(|(dx, dy)| self.step(dx, dy))
     }
 
+    /// |dx| + |dy|
     pub fn manhattan_dist(&self, other: &Coord) -> u64 {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+        let (dx, dy) = self.diff(other);
+        dx.unsigned_abs() + dy.unsigned_abs()
+    }
+
+    /// Integer midpoint between the two coords, or None if it would not be on the grid
+    /// (i.e. the sum of either component is odd)
+    pub fn midpoint(&self, other: &Coord) -> Option<Coord> {
+        let (sum_x, sum_y) = (self.x + other.x, self.y + other.y);
+        if sum_x % 2 != 0 || sum_y % 2 != 0 {
+            return None;
+        }
+
+        Some(Coord::new(sum_x / 2, sum_y / 2))
+    }
+
+    /// Multiply both components by factor, e.g. to repeat a velocity
+    pub fn scale(&self, factor: i64) -> Coord {
+        Coord::new(self.x * factor, self.y * factor)
+    }
+
+    /// max(|dx|, |dy|), i.e. the number of steps when diagonal moves are allowed
+    pub fn chebyshev_distance(&self, other: &Coord) -> u64 {
+        let (dx, dy) = self.diff(other);
+        dx.unsigned_abs().max(dy.unsigned_abs())
     }
 }
 
@@ -107,6 +216,66 @@ impl From<(i64, i64)> for Coord {
     }
 }
 
+/// Parse a coordinate from "x,y", ignoring surrounding whitespace
+impl FromStr for Coord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s
+            .trim()
+            .split_once(',')
+            .ok_or_else(|| format!("Expected x,y but did not find , in {s:?}"))?;
+
+        let parse = |val: &str| {
+            val.trim()
+                .parse()
+                .map_err(|e| format!("Failed to parse {val:?} in {s:?} as an integer: {e}"))
+        };
+
+        Ok(Coord::new(parse(x)?, parse(y)?))
+    }
+}
+
+impl Add<(i64, i64)> for Coord {
+    type Output = Coord;
+
+    fn add(self, (dx, dy): (i64, i64)) -> Coord {
+        self.step(dx, dy)
+    }
+}
+
+impl Sub<(i64, i64)> for Coord {
+    type Output = Coord;
+
+    fn sub(self, (dx, dy): (i64, i64)) -> Coord {
+        self.step(-dx, -dy)
+    }
+}
+
+impl Add<Coord> for Coord {
+    type Output = Coord;
+
+    fn add(self, other: Coord) -> Coord {
+        self.step(other.x, other.y)
+    }
+}
+
+impl Mul<i64> for Coord {
+    type Output = Coord;
+
+    fn mul(self, factor: i64) -> Coord {
+        self.scale(factor)
+    }
+}
+
+impl Sub<Coord> for Coord {
+    type Output = Coord;
+
+    fn sub(self, other: Coord) -> Coord {
+        Coord::from(self.diff(&other))
+    }
+}
+
 impl Debug for Coord {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
@@ -119,6 +288,254 @@ impl Display for Coord {
     }
 }
 
+// Greatest common divisor of the absolute values, using Euclid's algorithm
+fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// Smallest integer vector in the same direction as (dx, dy), i.e. divided by the gcd of
+/// its components. Signs are preserved, and (0, 0) is returned unchanged.
+pub fn reduce_vector(dx: i64, dy: i64) -> (i64, i64) {
+    match gcd(dx, dy) {
+        0 => (0, 0),
+        divisor => (dx / divisor, dy / divisor),
+    }
+}
+
+/// All coordinates within `radius` manhattan distance of `center` (including the center),
+/// along with their distance from the center.
+pub fn manhattan_ball(center: Coord, radius: i64) -> impl Iterator<Item = (Coord, i64)> {
+    (-radius..=radius).flat_map(move |dx| {
+        let remaining = radius - dx.abs();
+        (-remaining..=remaining).map(move |dy| (center.step(dx, dy), dx.abs() + dy.abs()))
+    })
+}
+
+/// Whether the straight line between `a` and `b` is unobstructed, using Bresenham's line
+/// algorithm to pick the cells along the line. Only the cells strictly between the two
+/// endpoints are checked with `is_blocked`.
+pub fn line_of_sight(a: &Coord, b: &Coord, is_blocked: impl Fn(&Coord) -> bool) -> bool {
+    let dx = (b.x - a.x).abs();
+    let dy = -(b.y - a.y).abs();
+    let sx = (b.x - a.x).signum();
+    let sy = (b.y - a.y).signum();
+
+    let mut err = dx + dy;
+    let mut curr = *a;
+
+    while curr != *b {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            curr.x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            curr.y += sy;
+        }
+
+        if curr != *b && is_blocked(&curr) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Shortest number of steps from `start` to every reachable coordinate, using breadth-first
+/// search where `neighbors` gives the coordinates which can be stepped to from a coordinate.
+pub fn bfs_distances(start: Coord, neighbors: impl Fn(Coord) -> Vec<Coord>) -> HashMap<Coord, u32> {
+    let mut distances = HashMap::from([(start, 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(pos) = queue.pop_front() {
+        let distance = distances[&pos];
+        for neighbor in neighbors(pos) {
+            // The first visit is always the shortest, since nodes are visited in order of distance
+            if let Entry::Vacant(entry) = distances.entry(neighbor) {
+                entry.insert(distance + 1);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    distances
+}
+
+// State with the cost to reach it, ordered so that BinaryHeap pops the lowest cost first
+struct QueueEntry<S> {
+    cost: usize,
+    state: S,
+}
+
+impl<S> PartialEq for QueueEntry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl<S> Eq for QueueEntry<S> {}
+
+impl<S> Ord for QueueEntry<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+
+impl<S> PartialOrd for QueueEntry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Lowest total cost from `start` to any state satisfying `is_goal` using Dijkstra's algorithm,
+/// where `successors` gives the states reachable in one move along with the cost of the move.
+/// Returns None if no goal is reachable.
+pub fn dijkstra<S: Eq + Hash + Clone>(
+    start: S,
+    is_goal: impl Fn(&S) -> bool,
+    successors: impl Fn(&S) -> Vec<(S, usize)>,
+) -> Option<usize> {
+    dijkstra_search(start, is_goal, successors).0
+}
+
+/// Lowest total cost from `start` to every reachable state, with `successors` as in `dijkstra`.
+pub fn dijkstra_costs<S: Eq + Hash + Clone>(
+    start: S,
+    successors: impl Fn(&S) -> Vec<(S, usize)>,
+) -> HashMap<S, usize> {
+    dijkstra_search(start, |_| false, successors).1
+}
+
+// Shared search for dijkstra and dijkstra_costs, returning the cost to the first goal found
+// along with the costs found so far. Costs are only final for every state if the search
+// runs until the queue is empty, i.e. no goal is found.
+fn dijkstra_search<S: Eq + Hash + Clone>(
+    start: S,
+    is_goal: impl Fn(&S) -> bool,
+    successors: impl Fn(&S) -> Vec<(S, usize)>,
+) -> (Option<usize>, HashMap<S, usize>) {
+    let mut costs = HashMap::from([(start.clone(), 0)]);
+    let mut queue = BinaryHeap::from([QueueEntry {
+        cost: 0,
+        state: start,
+    }]);
+
+    while let Some(QueueEntry { cost, state }) = queue.pop() {
+        if is_goal(&state) {
+            return (Some(cost), costs);
+        }
+
+        if cost > costs[&state] {
+            // stale entry, we have since found a lower cost to this state
+            continue;
+        }
+
+        for (next, move_cost) in successors(&state) {
+            let next_cost = cost + move_cost;
+            if costs.get(&next).is_none_or(|&cost| next_cost < cost) {
+                costs.insert(next.clone(), next_cost);
+                queue.push(QueueEntry {
+                    cost: next_cost,
+                    state: next,
+                });
+            }
+        }
+    }
+
+    (None, costs)
+}
+
+/// Same as `dijkstra`, but also returns every state on any lowest cost path from `start` to
+/// a goal (including both ends). Records the predecessors of each state on the way, then walks
+/// them back from the goals. Returns None if no goal is reachable, like `dijkstra`.
+pub fn dijkstra_all_paths<S: Eq + Hash + Clone>(
+    start: S,
+    is_goal: impl Fn(&S) -> bool,
+    successors: impl Fn(&S) -> Vec<(S, usize)>,
+) -> Option<(usize, HashSet<S>)> {
+    let mut costs = HashMap::from([(start.clone(), 0)]);
+    let mut predecessors: HashMap<S, Vec<S>> = HashMap::new();
+    let mut queue = BinaryHeap::from([QueueEntry {
+        cost: 0,
+        state: start,
+    }]);
+
+    let mut best_cost = None;
+    let mut goals = Vec::new();
+
+    while let Some(QueueEntry { cost, state }) = queue.pop() {
+        if best_cost.is_some_and(|best_cost| cost > best_cost) {
+            // All other paths cost more than the best path, so we can stop searching
+            break;
+        }
+
+        if cost > costs[&state] {
+            // stale entry, we have since found a lower cost to this state
+            continue;
+        }
+
+        if is_goal(&state) {
+            best_cost = Some(cost);
+            goals.push(state);
+            continue;
+        }
+
+        for (next, move_cost) in successors(&state) {
+            let next_cost = cost + move_cost;
+            match costs.get(&next) {
+                Some(&cost) if cost < next_cost => {
+                    // not a lowest cost path to next
+                }
+                Some(&cost) if cost == next_cost => {
+                    // another lowest cost path to next
+                    predecessors.entry(next).or_default().push(state.clone());
+                }
+                _ => {
+                    // new lowest cost path to next
+                    costs.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), vec![state.clone()]);
+                    queue.push(QueueEntry {
+                        cost: next_cost,
+                        state: next,
+                    });
+                }
+            }
+        }
+    }
+
+    // Walk back from the goals to find all the states on a lowest cost path
+    let mut on_path: HashSet<S> = goals.iter().cloned().collect();
+    let mut stack = goals;
+    while let Some(state) = stack.pop() {
+        for predecessor in predecessors.get(&state).into_iter().flatten() {
+            if on_path.insert(predecessor.clone()) {
+                stack.push(predecessor.clone());
+            }
+        }
+    }
+
+    best_cost.map(|best_cost| (best_cost, on_path))
+}
+
+/// Path from the start to `end` (including both), by following the predecessor of each
+/// state in `came_from` back until reaching a state with no predecessor, which is the start.
+/// `came_from` must not contain a cycle reachable from `end`.
+pub fn reconstruct_path<S: Eq + Hash + Clone>(came_from: &HashMap<S, S>, end: S) -> Vec<S> {
+    let mut path = vec![end];
+    while let Some(previous) = came_from.get(path.last().unwrap()) {
+        path.push(previous.clone());
+    }
+
+    path.reverse();
+    path
+}
+
 /// Dimensions of a 2D grid.
 #[derive(Clone, Copy, Debug)]
 pub struct Dimensions {
@@ -133,10 +550,36 @@ impl Dimensions {
 
     /// Assuming input is a 2-dimensional rectangular grid (i.e. all lines
     /// are the same length), return the dimensions of the grid.
+    /// Panics if the input is empty or ragged, see `try_from_input`
     pub fn from_input(input: &str) -> Dimensions {
+        Self::try_from_input(input).expect("Failed to get dimensions from input")
+    }
+
+    /// Dimensions of a grid input, or an error if it is empty or not every line has the
+    /// same length
+    pub fn try_from_input(input: &str) -> Result<Dimensions, String> {
+        let x_dim = input
+            .lines()
+            .next()
+            .ok_or_else(|| "Input is empty".to_string())?
+            .len();
+
+        for (y, line) in input.lines().enumerate() {
+            if line.len() != x_dim {
+                return Err(format!(
+                    "Line {y} has length {} but expected {x_dim}: {line:?}",
+                    line.len()
+                ));
+            }
+        }
+
         let y_dim = input.lines().count();
-        let x_dim = input.lines().next().unwrap().len();
-        Dimensions { x: x_dim, y: y_dim }
+        Ok(Dimensions { x: x_dim, y: y_dim })
+    }
+
+    /// Dimensions of the grid scaled by sx horizontally and sy vertically
+    pub fn scaled(&self, sx: usize, sy: usize) -> Dimensions {
+        Dimensions::new(self.x * sx, self.y * sy)
     }
 
     pub fn in_bounds(&self, coord: &Coord) -> bool {
@@ -148,7 +591,7 @@ impl Dimensions {
         let new_x = if coord.x < 0 {
             let diff = (-coord.x) % self.x as i64;
             (self.x as i64 - diff) % self.x as i64
-        } else if coord.x > self.x as i64 {
+        } else if coord.x >= self.x as i64 {
             (coord.x - self.x as i64) % self.x as i64
         } else {
             coord.x
@@ -157,7 +600,7 @@ impl Dimensions {
         let new_y = if coord.y < 0 {
             let diff = (-coord.y) % self.y as i64;
             (self.y as i64 - diff) % self.y as i64
-        } else if coord.y > self.y as i64 {
+        } else if coord.y >= self.y as i64 {
             (coord.y - self.y as i64) % self.y as i64
         } else {
             coord.y
@@ -166,10 +609,33 @@ impl Dimensions {
         Coord::new(new_x, new_y)
     }
 
+    /// Wrap around to the other side using the euclidean remainder on both axes,
+    /// so any offset (including large negative ones) ends up in bounds
+    pub fn wrap_signed(&self, coord: &Coord) -> Coord {
+        Coord::new(
+            coord.x.rem_euclid(self.x as i64),
+            coord.y.rem_euclid(self.y as i64),
+        )
+    }
+
     pub fn get_neighbors<'a>(&'a self, coord: &'a Coord) -> impl Iterator<Item = Coord> + 'a {
         coord.get_neighbors().filter(|coord| self.in_bounds(coord))
     }
 
+    /// Cardinal neighbors on a torus, i.e. wrapping around to the other side of the grid.
+    /// Neighbors are distinct and never include the coord itself, which matters when
+    /// the grid is only 1 or 2 cells wide or tall.
+    pub fn get_wrapping_neighbors(&self, coord: &Coord) -> impl Iterator<Item = Coord> {
+        let mut neighbors: Vec<Coord> = Vec::with_capacity(4);
+        for neighbor in coord.get_neighbors().map(|neighbor| self.wrap(&neighbor)) {
+            if neighbor != *coord && !neighbors.contains(&neighbor) {
+                neighbors.push(neighbor);
+            }
+        }
+
+        neighbors.into_iter()
+    }
+
     pub fn get_diagonal_neighbors<'a>(
         &'a self,
         coord: &'a Coord,
@@ -179,6 +645,12 @@ impl Dimensions {
             .filter(|coord| self.in_bounds(coord))
     }
 
+    /// All eight in bounds neighbors, cardinal then diagonal.
+    pub fn get_all_neighbors<'a>(&'a self, coord: &'a Coord) -> impl Iterator<Item = Coord> + 'a {
+        self.get_neighbors(coord)
+            .chain(self.get_diagonal_neighbors(coord))
+    }
+
     pub fn small_corner(&self) -> Coord {
         Coord::new(0, 0)
     }
@@ -187,6 +659,30 @@ impl Dimensions {
         Coord::new(self.x as i64 - 1, self.y as i64 - 1)
     }
 
+    /// Number of cells in the grid
+    pub fn area(&self) -> usize {
+        self.x * self.y
+    }
+
+    /// The four corners of the grid: bottom left, bottom right, top left, top right
+    pub fn corners(&self) -> [Coord; 4] {
+        let Coord { x: max_x, y: max_y } = self.large_corner();
+        [
+            self.small_corner(),
+            Coord::new(max_x, 0),
+            Coord::new(0, max_y),
+            self.large_corner(),
+        ]
+    }
+
+    /// Every in-bounds coordinate in row-major order, i.e. (0, 0), (1, 0), ..., (x - 1, 0),
+    /// (0, 1), ... so the first is `small_corner` and the last is `large_corner`.
+    /// This is the same order as `Grid::iter`.
+    pub fn iter_coords(&self) -> impl Iterator<Item = Coord> {
+        let (dim_x, dim_y) = (self.x as i64, self.y as i64);
+        (0..dim_y).flat_map(move |y| (0..dim_x).map(move |x| Coord::new(x, y)))
+    }
+
     pub fn left_borders(&self) -> impl Iterator<Item = Coord> + '_ {
         (0..self.y).map(|y| Coord::new(0, y as i64))
     }
@@ -202,6 +698,34 @@ impl Dimensions {
     pub fn top_borders(&self) -> impl Iterator<Item = Coord> + '_ {
         (0..self.x).map(|x| Coord::new(x as i64, self.y as i64 - 1))
     }
+
+    pub fn is_border(&self, coord: &Coord) -> bool {
+        !self.which_borders(coord).is_empty()
+    }
+
+    /// Return the borders that the coord lies on. Corners lie on two borders,
+    /// and coords that are out of bounds do not lie on any.
+    pub fn which_borders(&self, coord: &Coord) -> Vec<Side> {
+        if !self.in_bounds(coord) {
+            return Vec::new();
+        }
+
+        let mut sides = Vec::new();
+        if coord.x == 0 {
+            sides.push(Side::Left);
+        }
+        if coord.x == self.x as i64 - 1 {
+            sides.push(Side::Right);
+        }
+        if coord.y == self.y as i64 - 1 {
+            sides.push(Side::Top);
+        }
+        if coord.y == 0 {
+            sides.push(Side::Bottom);
+        }
+
+        sides
+    }
 }
 
 #[derive(Clone)]
@@ -210,11 +734,142 @@ pub struct Grid<T> {
     pub values: Vec<Vec<T>>,
 }
 
+impl<T> Grid<T> {
+    /// Iterate over each cell with its coordinate in row-major order,
+    /// i.e. all cells in `values[0]` first, with y indexing `values` and x indexing each row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> + '_ {
+        self.values.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, val)| (Coord::new(x as i64, y as i64), val))
+        })
+    }
+
+    /// First coordinate whose value matches pred, scanning in the same order as `iter`
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<Coord> {
+        self.iter()
+            .find(|(_, val)| pred(val))
+            .map(|(coord, _)| coord)
+    }
+
+    /// All coordinates whose value matches pred, in the same order as `iter`
+    pub fn find_all(&self, pred: impl Fn(&T) -> bool) -> Vec<Coord> {
+        self.iter()
+            .filter(|(_, val)| pred(val))
+            .map(|(coord, _)| coord)
+            .collect()
+    }
+
+    /// New grid of the same dimensions, with each cell transformed by f
+    /// which also receives the cell's coordinate
+    pub fn map<U>(&self, f: impl Fn(&Coord, &T) -> U) -> Grid<U> {
+        let values = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, val)| f(&Coord::new(x as i64, y as i64), val))
+                    .collect()
+            })
+            .collect();
+
+        Grid {
+            dimensions: self.dimensions,
+            values,
+        }
+    }
+
+    /// Render the grid as one line per row, converting each cell with f.
+    /// Rows are in the same order as `from_input`, so the output can be parsed back in.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        self.values
+            .iter()
+            .map(|row| row.iter().map(&f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Borrow the value at coord, or None if it is out of bounds
+    pub fn get_ref(&self, coord: &Coord) -> Option<&T> {
+        if !self.dimensions.in_bounds(coord) {
+            return None;
+        }
+
+        self.values.get(coord.y as usize)?.get(coord.x as usize)
+    }
+
+    /// Mutably borrow the value at coord, or None if it is out of bounds
+    pub fn get_mut(&mut self, coord: &Coord) -> Option<&mut T> {
+        if !self.dimensions.in_bounds(coord) {
+            return None;
+        }
+
+        self.values
+            .get_mut(coord.y as usize)?
+            .get_mut(coord.x as usize)
+    }
+
+    /// Overwrite the value at coord, returning false (and leaving the grid unchanged)
+    /// if it is out of bounds
+    pub fn set(&mut self, coord: &Coord, val: T) -> bool {
+        match self.get_mut(coord) {
+            Some(cell) => {
+                *cell = val;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 impl<T: Clone> Grid<T> {
+    /// Panics if the values don't match the dimensions, see `try_new`.
     pub fn new(dimensions: Dimensions, values: Vec<Vec<T>>) -> Self {
-        assert_eq!(dimensions.x, values[0].len());
-        assert_eq!(dimensions.y, values.len());
-        Grid { dimensions, values }
+        Grid::try_new(dimensions, values).expect("Invalid grid")
+    }
+
+    /// Grid with the given values, or an error if there are no rows or the number of rows
+    /// or the length of any row doesn't match the dimensions
+    pub fn try_new(dimensions: Dimensions, values: Vec<Vec<T>>) -> Result<Grid<T>, String> {
+        if values.is_empty() {
+            return Err("Grid has no rows".to_string());
+        }
+
+        if values.len() != dimensions.y {
+            return Err(format!(
+                "Grid has {} rows but expected {}",
+                values.len(),
+                dimensions.y
+            ));
+        }
+
+        for (y, row) in values.iter().enumerate() {
+            if row.len() != dimensions.x {
+                return Err(format!(
+                    "Row {y} has length {} but expected {}",
+                    row.len(),
+                    dimensions.x
+                ));
+            }
+        }
+
+        Ok(Grid { dimensions, values })
+    }
+
+    /// Build a grid from the input, converting each char with f.
+    /// Rows are kept top-to-bottom as written, so the first line of the input is y = 0
+    /// (unlike `parse_maze`, which puts the last line at y = 0).
+    /// Panics if the input is empty or ragged.
+    pub fn from_input(input: &str, f: impl Fn(char) -> T) -> Grid<T> {
+        let dimensions = Dimensions::from_input(input);
+        let values = input
+            .lines()
+            .map(|line| line.chars().map(&f).collect())
+            .collect();
+
+        Grid::new(dimensions, values)
     }
 
     pub fn in_bounds(&self, coord: &Coord) -> bool {
@@ -222,14 +877,7 @@ impl<T: Clone> Grid<T> {
     }
 
     pub fn get(&self, coord: &Coord) -> Option<T> {
-        if !self.in_bounds(coord) {
-            return None;
-        }
-
-        self.values
-            .get(coord.y as usize)?
-            .get(coord.x as usize)
-            .cloned()
+        self.get_ref(coord).cloned()
     }
 
     pub fn get_neighbors<'a>(&'a self, coord: &'a Coord) -> impl Iterator<Item = Coord> + 'a {
@@ -243,60 +891,387 @@ impl<T: Clone> Grid<T> {
         self.dimensions.get_diagonal_neighbors(coord)
     }
 
+    pub fn get_all_neighbors<'a>(&'a self, coord: &'a Coord) -> impl Iterator<Item = Coord> + 'a {
+        self.dimensions.get_all_neighbors(coord)
+    }
+
     pub fn positions_of(&self, val: &T) -> HashSet<Coord>
     where
         T: PartialEq,
     {
-        let mut positions = HashSet::new();
-        for x in 0..self.dimensions.x {
-            for y in 0..self.dimensions.y {
-                let coord = Coord::new(x as i64, y as i64);
-                if self.get(&coord).as_ref() == Some(val) {
-                    positions.insert(coord);
-                }
-            }
+        self.iter()
+            .filter(|(_, v)| *v == val)
+            .map(|(coord, _)| coord)
+            .collect()
+    }
+
+    /// Group every coordinate by its value in a single pass over the grid.
+    /// Coordinates in each bucket are in the same order as `iter`.
+    pub fn value_positions(&self) -> HashMap<T, Vec<Coord>>
+    where
+        T: Eq + Hash,
+    {
+        let mut positions: HashMap<T, Vec<Coord>> = HashMap::new();
+        for (coord, val) in self.iter() {
+            positions.entry(val.clone()).or_default().push(coord);
         }
 
         positions
     }
-}
 
-pub struct Maze {
-    pub start: Coord,
-    pub end: Coord,
-    pub walls: HashSet<Coord>,
-}
+    /// Same as `positions_of`, but scans the rows in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_positions_of(&self, val: &T) -> HashSet<Coord>
+    where
+        T: PartialEq + Sync,
+    {
+        use rayon::prelude::*;
 
-pub fn parse_maze(input: &str) -> Maze {
-    let mut start = None;
-    let mut end = None;
-    let mut walls = HashSet::new();
+        self.values
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(move |(_, v)| *v == val)
+                    .map(move |(x, _)| Coord::new(x as i64, y as i64))
+            })
+            .collect()
+    }
 
-    // Use coordinate system with (0,0) at bottom left
-    for (y, line) in input.lines().rev().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let coord = Coord::new(x as i64, y as i64);
-            if c == 'S' {
-                start = Some(coord);
-            } else if c == 'E' {
-                end = Some(coord);
-            } else if c == '#' {
+    /// Return the connected region of cells with the same value as the start cell,
+    /// optionally also connecting through diagonal neighbors.
+    pub fn flood_fill(&self, start: &Coord, diagonal: bool) -> HashSet<Coord>
+    where
+        T: PartialEq,
+    {
+        let mut region = HashSet::new();
+        let Some(val) = self.get(start) else {
+            return region;
+        };
+
+        region.insert(*start);
+        let mut to_visit = VecDeque::from([*start]);
+
+        while let Some(next) = to_visit.pop_front() {
+            let diagonal_neighbors = diagonal
+                .then(|| self.get_diagonal_neighbors(&next))
+                .into_iter()
+                .flatten();
+
+            for neighbor in self.get_neighbors(&next).chain(diagonal_neighbors) {
+                if !region.contains(&neighbor) && self.get(&neighbor).as_ref() == Some(&val) {
+                    region.insert(neighbor);
+                    to_visit.push_back(neighbor);
+                }
+            }
+        }
+
+        region
+    }
+
+    /// New grid with rows and columns swapped, so the value at (x, y) moves to (y, x).
+    pub fn transpose(&self) -> Grid<T> {
+        let values = (0..self.dimensions.x)
+            .map(|x| self.values.iter().map(|row| row[x].clone()).collect())
+            .collect();
+
+        Grid::new(
+            Dimensions::new(self.dimensions.y, self.dimensions.x),
+            values,
+        )
+    }
+
+    /// New grid rotated a quarter turn clockwise, as the grid would appear when rendered.
+    pub fn rotate_cw(&self) -> Grid<T> {
+        let values = (0..self.dimensions.x)
+            .map(|x| self.values.iter().rev().map(|row| row[x].clone()).collect())
+            .collect();
+
+        Grid::new(
+            Dimensions::new(self.dimensions.y, self.dimensions.x),
+            values,
+        )
+    }
+
+    /// New grid rotated a quarter turn counter-clockwise, as the grid would appear when rendered.
+    pub fn rotate_ccw(&self) -> Grid<T> {
+        let values = (0..self.dimensions.x)
+            .rev()
+            .map(|x| self.values.iter().map(|row| row[x].clone()).collect())
+            .collect();
+
+        Grid::new(
+            Dimensions::new(self.dimensions.y, self.dimensions.x),
+            values,
+        )
+    }
+
+    /// New grid mirrored left to right, i.e. with the columns in reverse order.
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        let values = self
+            .values
+            .iter()
+            .map(|row| row.iter().rev().cloned().collect())
+            .collect();
+
+        Grid::new(self.dimensions, values)
+    }
+
+    /// New grid mirrored top to bottom, i.e. with the rows in reverse order.
+    pub fn flip_vertical(&self) -> Grid<T> {
+        let values = self.values.iter().rev().cloned().collect();
+
+        Grid::new(self.dimensions, values)
+    }
+
+    /// Rectangular window of the grid with the given dimensions, where top_left is the
+    /// corner with the smallest x and y (the top left as the grid is rendered).
+    /// Returns None if any part of the window is out of bounds.
+    pub fn subgrid(&self, top_left: Coord, dims: Dimensions) -> Option<Grid<T>> {
+        if !self.in_bounds(&top_left) || dims.x == 0 || dims.y == 0 {
+            return None;
+        }
+
+        let (x, y) = (top_left.x as usize, top_left.y as usize);
+        if x + dims.x > self.dimensions.x || y + dims.y > self.dimensions.y {
+            return None;
+        }
+
+        let values = self.values[y..y + dims.y]
+            .iter()
+            .map(|row| row[x..x + dims.x].to_vec())
+            .collect();
+
+        Some(Grid::new(dims, values))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Maze {
+    pub start: Coord,
+    pub end: Coord,
+    pub walls: HashSet<Coord>,
+}
+
+/// Builds a `Maze` programmatically instead of parsing it from a string.
+/// Coordinates use the same system as `parse_maze`, with (0,0) at bottom left.
+pub struct MazeBuilder {
+    dimensions: Dimensions,
+    start: Option<Coord>,
+    end: Option<Coord>,
+    walls: HashSet<Coord>,
+}
+
+impl MazeBuilder {
+    pub fn with_dimensions(dimensions: Dimensions) -> Self {
+        MazeBuilder {
+            dimensions,
+            start: None,
+            end: None,
+            walls: HashSet::new(),
+        }
+    }
+
+    pub fn wall(mut self, coord: Coord) -> Self {
+        assert!(
+            self.dimensions.in_bounds(&coord),
+            "Wall {coord} out of bounds"
+        );
+        self.walls.insert(coord);
+        self
+    }
+
+    pub fn start(mut self, coord: Coord) -> Self {
+        assert!(
+            self.dimensions.in_bounds(&coord),
+            "Start {coord} out of bounds"
+        );
+        self.start = Some(coord);
+        self
+    }
+
+    pub fn end(mut self, coord: Coord) -> Self {
+        assert!(
+            self.dimensions.in_bounds(&coord),
+            "End {coord} out of bounds"
+        );
+        self.end = Some(coord);
+        self
+    }
+
+    pub fn build(self) -> Maze {
+        Maze {
+            start: self.start.expect("Did not set starting position"),
+            end: self.end.expect("Did not set end position"),
+            walls: self.walls,
+        }
+    }
+}
+
+pub fn parse_maze(input: &str) -> Maze {
+    parse_maze_with_dimensions(input).0
+}
+
+/// Same as `parse_maze`, but returns an error instead of panicking if there is not exactly
+/// one start S and one end E, or if the input is empty or ragged.
+pub fn try_parse_maze(input: &str) -> Result<Maze, String> {
+    try_parse_maze_with_dimensions(input).map(|(maze, _)| maze)
+}
+
+/// Same as `parse_maze`, also returning the dimensions of the input.
+/// Panics if the input is ragged, like `Dimensions::from_input`.
+pub fn parse_maze_with_dimensions(input: &str) -> (Maze, Dimensions) {
+    try_parse_maze_with_dimensions(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+fn try_parse_maze_with_dimensions(input: &str) -> Result<(Maze, Dimensions), String> {
+    let dimensions = Dimensions::try_from_input(input)?;
+    let mut start = None;
+    let mut end = None;
+    let mut walls = HashSet::new();
+
+    // Use coordinate system with (0,0) at bottom left
+    for (y, line) in input.lines().rev().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            let coord = Coord::new(x as i64, y as i64);
+            if c == 'S' {
+                if let Some(other) = start.replace(coord) {
+                    return Err(format!(
+                        "Found more than one starting position S, at {other} and {coord}"
+                    ));
+                }
+            } else if c == 'E' {
+                if let Some(other) = end.replace(coord) {
+                    return Err(format!(
+                        "Found more than one end position E, at {other} and {coord}"
+                    ));
+                }
+            } else if c == '#' {
                 walls.insert(coord);
             }
         }
     }
 
-    Maze {
-        start: start.expect("Did not find starting position S"),
-        end: end.expect("Did not find end position E"),
+    let maze = Maze {
+        start: start.ok_or_else(|| "Did not find starting position S".to_string())?,
+        end: end.ok_or_else(|| "Did not find end position E".to_string())?,
         walls,
+    };
+
+    Ok((maze, dimensions))
+}
+
+/// Parse a grid of chars, also returning the positions of each of the marker chars
+/// (e.g. the start or a robot). Every marker has an entry, which is empty if the marker
+/// does not appear. Uses the same coordinate system as `Grid::from_input`, with (0,0) at
+/// top left, so the grid can be rendered back out with `Grid::render`.
+pub fn parse_grid_with_markers(
+    input: &str,
+    markers: &[char],
+) -> (Grid<char>, HashMap<char, Vec<Coord>>) {
+    let grid = Grid::from_input(input, |c| c);
+
+    let mut positions: HashMap<char, Vec<Coord>> =
+        markers.iter().map(|marker| (*marker, Vec::new())).collect();
+    for (coord, c) in grid.iter() {
+        if let Some(marker_positions) = positions.get_mut(c) {
+            marker_positions.push(coord);
+        }
     }
+
+    (grid, positions)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_direction_all() {
+        let dirs: Vec<Direction> = Direction::all().collect();
+        assert_eq!(
+            dirs,
+            [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right
+            ]
+        );
+
+        let offsets: Vec<(i64, i64)> = Direction::all().map(Direction::to_dx_dy).collect();
+        assert_eq!(offsets, [(0, 1), (0, -1), (-1, 0), (1, 0)]);
+
+        for (i, dir) in Direction::all().enumerate() {
+            assert_eq!(dir.to_index(), i);
+        }
+    }
+
+    #[test]
+    fn test_direction_char() {
+        for c in ['^', 'v', '<', '>'] {
+            assert_eq!(Direction::from_char(c).map(Direction::to_char), Some(c));
+        }
+        for dir in Direction::all() {
+            assert_eq!(Direction::from_char(dir.to_char()), Some(dir));
+        }
+
+        assert_eq!(Direction::from_char('^'), Some(Direction::Up));
+        assert_eq!(Direction::from_char('x'), None);
+        assert_eq!(Direction::from_char('V'), None);
+    }
+
+    #[test]
+    fn test_direction_opposite() {
+        for dir in [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_eq!(dir.opposite(), dir.turn_left().turn_left());
+            assert_eq!(dir.turn_180(), dir.opposite());
+        }
+
+        assert_eq!(Direction::Up.opposite(), Direction::Down);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+    }
+
+    #[test]
+    fn test_direction_between() {
+        let from = Coord::new(3, 3);
+        for dir in Direction::all() {
+            assert_eq!(
+                Direction::between(&from, &from.step_in_direction(dir)),
+                Some(dir)
+            );
+        }
+
+        assert_eq!(Direction::between(&from, &Coord::new(4, 4)), None);
+        assert_eq!(Direction::between(&from, &Coord::new(5, 3)), None);
+        assert_eq!(Direction::between(&from, &from), None);
+    }
+
+    #[test]
+    fn test_direction_index() {
+        for dir in [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            assert_eq!(Direction::from_index(dir.to_index()), Some(dir));
+        }
+
+        for i in 0..4 {
+            assert_eq!(Direction::from_index(i).unwrap().to_index(), i);
+        }
+
+        assert_eq!(Direction::from_index(4), None);
+    }
+
     #[test]
     fn test_dim_wrap() {
         let dim = Dimensions::new(10, 10);
@@ -326,5 +1301,801 @@ mod test {
             Dimensions::new(11, 7).wrap(&Coord::new(4, -100)),
             Coord::new(4, 5)
         );
+
+        // exactly on the upper boundary
+        assert_eq!(dim.wrap(&Coord::new(10, 0)), Coord::new(0, 0));
+        assert_eq!(dim.wrap(&Coord::new(0, 10)), Coord::new(0, 0));
+        assert_eq!(dim.wrap(&Coord::new(20, 30)), Coord::new(0, 0));
+        assert_eq!(dim.wrap(&Coord::new(9, 9)), Coord::new(9, 9));
+    }
+
+    #[test]
+    fn test_dim_get_wrapping_neighbors() {
+        let dim = Dimensions::new(3, 3);
+
+        let neighbors: HashSet<Coord> = dim.get_wrapping_neighbors(&Coord::new(0, 0)).collect();
+        assert_eq!(
+            neighbors,
+            HashSet::from([
+                Coord::new(1, 0),
+                Coord::new(2, 0),
+                Coord::new(0, 1),
+                Coord::new(0, 2)
+            ])
+        );
+
+        let neighbors: HashSet<Coord> = dim.get_wrapping_neighbors(&Coord::new(2, 1)).collect();
+        assert_eq!(
+            neighbors,
+            HashSet::from([
+                Coord::new(0, 1),
+                Coord::new(1, 1),
+                Coord::new(2, 2),
+                Coord::new(2, 0)
+            ])
+        );
+
+        // Left and right neighbors are the same cell
+        let dim = Dimensions::new(2, 3);
+        assert_eq!(dim.get_wrapping_neighbors(&Coord::new(0, 1)).count(), 3);
+
+        // Only neighbors are the cell itself
+        let dim = Dimensions::new(1, 1);
+        assert_eq!(dim.get_wrapping_neighbors(&Coord::new(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_dim_try_from_input() {
+        let dim = Dimensions::try_from_input("...\n.#.\n...\n").unwrap();
+        assert_eq!((dim.x, dim.y), (3, 3));
+
+        assert!(Dimensions::try_from_input("...\n.#\n...").is_err());
+        assert!(Dimensions::try_from_input("...\n.#..\n...").is_err());
+        assert!(Dimensions::try_from_input("").is_err());
+    }
+
+    #[test]
+    fn test_dim_area_and_corners() {
+        assert_eq!(Dimensions::new(10, 10).area(), 100);
+        assert_eq!(Dimensions::new(101, 103).area(), 10403);
+        assert_eq!(Dimensions::new(1, 1).area(), 1);
+        assert_eq!(Dimensions::new(0, 5).area(), 0);
+
+        let dim = Dimensions::new(4, 3);
+        let corners = dim.corners();
+        assert!(corners.contains(&dim.small_corner()));
+        assert!(corners.contains(&dim.large_corner()));
+        assert_eq!(
+            corners,
+            [
+                Coord::new(0, 0),
+                Coord::new(3, 0),
+                Coord::new(0, 2),
+                Coord::new(3, 2)
+            ]
+        );
+        assert!(corners.iter().all(|corner| dim.in_bounds(corner)));
+    }
+
+    #[test]
+    fn test_dim_iter_coords() {
+        let dim = Dimensions::new(4, 3);
+        let coords: Vec<Coord> = dim.iter_coords().collect();
+
+        assert_eq!(coords.len(), 4 * 3);
+        assert_eq!(coords.first(), Some(&dim.small_corner()));
+        assert_eq!(coords.last(), Some(&dim.large_corner()));
+        assert_eq!(coords[1], Coord::new(1, 0));
+        assert_eq!(coords[4], Coord::new(0, 1));
+        assert!(coords.iter().all(|coord| dim.in_bounds(coord)));
+
+        assert_eq!(Dimensions::new(0, 3).iter_coords().count(), 0);
+    }
+
+    #[test]
+    fn test_dim_scaled() {
+        let dim = Dimensions::new(11, 7).scaled(2, 1);
+        assert_eq!((dim.x, dim.y), (22, 7));
+
+        let dim = Dimensions::new(11, 7).scaled(3, 4);
+        assert_eq!((dim.x, dim.y), (33, 28));
+    }
+
+    #[test]
+    fn test_dim_wrap_signed() {
+        let dim = Dimensions::new(11, 7);
+
+        // in bounds
+        assert_eq!(dim.wrap_signed(&Coord::new(5, 5)), Coord::new(5, 5));
+
+        // exact multiples of the dimension
+        assert_eq!(dim.wrap_signed(&Coord::new(11, 7)), Coord::new(0, 0));
+        assert_eq!(dim.wrap_signed(&Coord::new(-22, -14)), Coord::new(0, 0));
+
+        // large negative offsets
+        assert_eq!(dim.wrap_signed(&Coord::new(-1000, -1000)), Coord::new(1, 1));
+        assert_eq!(dim.wrap_signed(&Coord::new(4, -100)), Coord::new(4, 5));
+
+        // large positive offsets
+        assert_eq!(dim.wrap_signed(&Coord::new(1000, 1000)), Coord::new(10, 6));
+
+        for v in -50..50 {
+            assert_eq!(
+                dim.wrap_signed(&Coord::new(v, v)),
+                Coord::new(v.rem_euclid(11), v.rem_euclid(7))
+            );
+        }
+    }
+
+    #[test]
+    fn test_which_borders() {
+        let dim = Dimensions::new(5, 4);
+
+        // corners
+        assert_eq!(
+            dim.which_borders(&Coord::new(0, 0)),
+            vec![Side::Left, Side::Bottom]
+        );
+        assert_eq!(
+            dim.which_borders(&Coord::new(4, 3)),
+            vec![Side::Right, Side::Top]
+        );
+
+        // edges
+        assert_eq!(dim.which_borders(&Coord::new(0, 2)), vec![Side::Left]);
+        assert_eq!(dim.which_borders(&Coord::new(4, 1)), vec![Side::Right]);
+        assert_eq!(dim.which_borders(&Coord::new(2, 3)), vec![Side::Top]);
+        assert_eq!(dim.which_borders(&Coord::new(2, 0)), vec![Side::Bottom]);
+        assert!(dim.is_border(&Coord::new(2, 0)));
+
+        // interior
+        assert!(dim.which_borders(&Coord::new(2, 2)).is_empty());
+        assert!(!dim.is_border(&Coord::new(2, 2)));
+    }
+
+    #[test]
+    fn test_manhattan_ball() {
+        let center = Coord::new(3, -1);
+        let points: Vec<(Coord, i64)> = manhattan_ball(center, 2).collect();
+        assert_eq!(points.len(), 13);
+
+        let unique: HashSet<Coord> = points.iter().map(|(coord, _)| *coord).collect();
+        assert_eq!(unique.len(), 13);
+        assert!(unique.contains(&center));
+
+        for (coord, dist) in points {
+            assert_eq!(coord.manhattan_dist(&center), dist as u64);
+            assert!(dist <= 2);
+        }
+
+        assert_eq!(manhattan_ball(center, 0).collect::<Vec<_>>(), [(center, 0)]);
+    }
+
+    #[test]
+    fn test_coord_from_str() {
+        assert_eq!("3,4".parse(), Ok(Coord::new(3, 4)));
+        assert_eq!("  6,1\n".parse(), Ok(Coord::new(6, 1)));
+        assert_eq!("-2,-10".parse(), Ok(Coord::new(-2, -10)));
+
+        assert!("34".parse::<Coord>().is_err());
+        assert!("".parse::<Coord>().is_err());
+        assert!("a,4".parse::<Coord>().is_err());
+        assert!("3,".parse::<Coord>().is_err());
+        assert!("3,4,5".parse::<Coord>().is_err());
+    }
+
+    #[test]
+    fn test_coord_ops() {
+        let a = Coord::new(3, -2);
+        let b = Coord::new(-1, 5);
+
+        assert_eq!(a + (4, 1), a.step(4, 1));
+        assert_eq!(a - (4, 1), a.step(-4, -1));
+        assert_eq!(a - b, Coord::from(a.diff(&b)));
+        assert_eq!(a - b, Coord::new(4, -7));
+        assert_eq!(a + b, Coord::new(2, 3));
+        assert_eq!(b + (a - b), a);
+    }
+
+    #[test]
+    fn test_coord_midpoint() {
+        let a = Coord::new(1, -3);
+        assert_eq!(a.midpoint(&Coord::new(5, 3)), Some(Coord::new(3, 0)));
+        assert_eq!(a.midpoint(&Coord::new(-5, -3)), Some(Coord::new(-2, -3)));
+        assert_eq!(a.midpoint(&a), Some(a));
+
+        assert_eq!(a.midpoint(&Coord::new(2, 3)), None);
+        assert_eq!(a.midpoint(&Coord::new(1, 0)), None);
+    }
+
+    #[test]
+    fn test_coord_scale() {
+        let a = Coord::new(3, -2);
+
+        assert_eq!(a.scale(4), Coord::new(12, -8));
+        assert_eq!(a.scale(0), Coord::new(0, 0));
+        assert_eq!(a.scale(-1), Coord::new(-3, 2));
+        assert_eq!(a * -3, a.scale(-3));
+        assert_eq!(Coord::new(1, 1) + a * 2, Coord::new(7, -3));
+    }
+
+    #[test]
+    fn test_coord_distances() {
+        let a = Coord::new(2, -3);
+        assert_eq!(a.manhattan_dist(&a), 0);
+        assert_eq!(a.chebyshev_distance(&a), 0);
+
+        // axis-aligned
+        let b = Coord::new(-3, -3);
+        assert_eq!(a.manhattan_dist(&b), 5);
+        assert_eq!(a.chebyshev_distance(&b), 5);
+
+        // diagonal
+        let c = Coord::new(5, 1);
+        assert_eq!(a.manhattan_dist(&c), 7);
+        assert_eq!(a.chebyshev_distance(&c), 4);
+        assert_eq!(c.manhattan_dist(&a), 7);
+        assert_eq!(c.chebyshev_distance(&a), 4);
+    }
+
+    #[test]
+    fn test_line_of_sight() {
+        // .....
+        // ..#..
+        // .....
+        let walls = HashSet::from([Coord::new(2, 1)]);
+        let is_blocked = |coord: &Coord| walls.contains(coord);
+
+        // Straight through the wall
+        assert!(!line_of_sight(
+            &Coord::new(0, 1),
+            &Coord::new(4, 1),
+            is_blocked
+        ));
+        // Diagonal through the wall
+        assert!(!line_of_sight(
+            &Coord::new(0, 0),
+            &Coord::new(4, 2),
+            is_blocked
+        ));
+
+        // Along the bottom row, and diagonally past the wall
+        assert!(line_of_sight(
+            &Coord::new(0, 0),
+            &Coord::new(4, 0),
+            is_blocked
+        ));
+        assert!(line_of_sight(
+            &Coord::new(0, 0),
+            &Coord::new(2, 2),
+            is_blocked
+        ));
+
+        // Endpoints themselves are not checked
+        assert!(line_of_sight(
+            &Coord::new(2, 1),
+            &Coord::new(2, 1),
+            is_blocked
+        ));
+        assert!(line_of_sight(
+            &Coord::new(0, 1),
+            &Coord::new(2, 1),
+            is_blocked
+        ));
+    }
+
+    #[test]
+    fn test_grid_try_new() {
+        let grid = Grid::try_new(Dimensions::new(2, 2), vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(grid.get(&Coord::new(1, 0)), Some(2));
+
+        let empty: Vec<Vec<u8>> = vec![];
+        assert_eq!(
+            Grid::try_new(Dimensions::new(0, 0), empty).err(),
+            Some("Grid has no rows".to_string())
+        );
+
+        assert_eq!(
+            Grid::try_new(Dimensions::new(2, 2), vec![vec![1, 2], vec![3]]).err(),
+            Some("Row 1 has length 1 but expected 2".to_string())
+        );
+
+        assert_eq!(
+            Grid::try_new(Dimensions::new(2, 3), vec![vec![1, 2], vec![3, 4]]).err(),
+            Some("Grid has 2 rows but expected 3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_grid_from_input() {
+        let grid = Grid::from_input("ab\ncd\nef", |c| c);
+        assert_eq!((grid.dimensions.x, grid.dimensions.y), (2, 3));
+        assert_eq!(grid.get(&Coord::new(0, 0)), Some('a'));
+        assert_eq!(grid.get(&Coord::new(1, 0)), Some('b'));
+        assert_eq!(grid.get(&Coord::new(1, 2)), Some('f'));
+
+        let grid = Grid::from_input("012\n789\n", |c| c as u8 - b'0');
+        assert_eq!((grid.dimensions.x, grid.dimensions.y), (3, 2));
+        assert_eq!(grid.values, vec![vec![0, 1, 2], vec![7, 8, 9]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_grid_from_input_ragged() {
+        Grid::from_input("ab\nc", |c| c);
+    }
+
+    #[test]
+    fn test_grid_render() {
+        // Round trip through from_input, with a non-symmetric grid so the orientation matters
+        let input = "#..\n.#.\n..@";
+        let grid = Grid::from_input(input, |c| c);
+        assert_eq!(grid.get(&Coord::new(0, 0)), Some('#'));
+        assert_eq!(grid.render(|c| *c), input);
+
+        let digits: Grid<u8> = Grid::from_input("012\n789", |c| c as u8 - b'0');
+        assert_eq!(
+            digits.render(|d| if *d > 5 { '#' } else { '.' }),
+            "...\n###"
+        );
+    }
+
+    #[test]
+    fn test_grid_rotations() {
+        // 2 wide, 3 tall
+        let grid = Grid::from_input("ab\ncd\nef", |c| c);
+
+        let transposed = grid.transpose();
+        assert_eq!((transposed.dimensions.x, transposed.dimensions.y), (3, 2));
+        assert_eq!(transposed.render(|c| *c), "ace\nbdf");
+
+        let cw = grid.rotate_cw();
+        assert_eq!((cw.dimensions.x, cw.dimensions.y), (3, 2));
+        assert_eq!(cw.render(|c| *c), "eca\nfdb");
+
+        let ccw = grid.rotate_ccw();
+        assert_eq!((ccw.dimensions.x, ccw.dimensions.y), (3, 2));
+        assert_eq!(ccw.render(|c| *c), "bdf\nace");
+
+        assert_eq!(cw.rotate_ccw().values, grid.values);
+        assert_eq!(transposed.transpose().values, grid.values);
+
+        let full_turn = grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw();
+        assert_eq!(
+            (full_turn.dimensions.x, full_turn.dimensions.y),
+            (grid.dimensions.x, grid.dimensions.y)
+        );
+        assert_eq!(full_turn.values, grid.values);
+    }
+
+    #[test]
+    fn test_grid_flips() {
+        let grid = Grid::from_input("ab\ncd", |c| c);
+
+        let horizontal = grid.flip_horizontal();
+        assert_eq!(horizontal.render(|c| *c), "ba\ndc");
+        assert_eq!(horizontal.flip_horizontal().values, grid.values);
+
+        let vertical = grid.flip_vertical();
+        assert_eq!(vertical.render(|c| *c), "cd\nab");
+        assert_eq!(vertical.flip_vertical().values, grid.values);
+
+        // Non-square grids keep their dimensions
+        let grid = Grid::from_input("abc\ndef", |c| c);
+        for flipped in [grid.flip_horizontal(), grid.flip_vertical()] {
+            assert_eq!((flipped.dimensions.x, flipped.dimensions.y), (3, 2));
+        }
+    }
+
+    #[test]
+    fn test_grid_get_all_neighbors() {
+        let grid = Grid::from_input("abc\ndef\nghi", |c| c);
+
+        let center: HashSet<Coord> = grid.get_all_neighbors(&Coord::new(1, 1)).collect();
+        assert_eq!(center.len(), 8);
+        assert!(!center.contains(&Coord::new(1, 1)));
+
+        let corner: HashSet<Coord> = grid.get_all_neighbors(&Coord::new(0, 0)).collect();
+        assert_eq!(
+            corner,
+            HashSet::from([Coord::new(1, 0), Coord::new(0, 1), Coord::new(1, 1)])
+        );
+    }
+
+    #[test]
+    fn test_grid_find() {
+        let grid = Grid::from_input("#.#\n.@.\n#.#", |c| c);
+
+        assert_eq!(grid.find(|c| *c == '@'), Some(Coord::new(1, 1)));
+        assert_eq!(grid.find(|c| *c == 'E'), None);
+
+        // Scans row by row, so the first match is in the first row
+        assert_eq!(grid.find(|c| *c == '#'), Some(Coord::new(0, 0)));
+        assert_eq!(
+            grid.find_all(|c| *c == '#'),
+            [
+                Coord::new(0, 0),
+                Coord::new(2, 0),
+                Coord::new(0, 2),
+                Coord::new(2, 2)
+            ]
+        );
+        assert!(grid.find_all(|c| c.is_ascii_digit()).is_empty());
+    }
+
+    #[test]
+    fn test_grid_value_positions() {
+        let grid = Grid::from_input("a.b\n.a.\nb..", |c| c);
+        let positions = grid.value_positions();
+
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions[&'a'], [Coord::new(0, 0), Coord::new(1, 1)]);
+        assert_eq!(positions[&'b'], [Coord::new(2, 0), Coord::new(0, 2)]);
+        assert_eq!(positions[&'.'].len(), 5);
+
+        // Agrees with positions_of for every value
+        for (val, coords) in positions {
+            assert_eq!(grid.positions_of(&val), coords.into_iter().collect());
+        }
+    }
+
+    #[test]
+    fn test_grid_subgrid() {
+        let grid = Grid::from_input("abcd\nefgh\nijkl", |c| c);
+
+        let window = grid
+            .subgrid(Coord::new(1, 1), Dimensions::new(2, 2))
+            .unwrap();
+        assert_eq!((window.dimensions.x, window.dimensions.y), (2, 2));
+        assert_eq!(window.render(|c| *c), "fg\njk");
+
+        // The whole grid
+        let whole = grid.subgrid(Coord::new(0, 0), grid.dimensions).unwrap();
+        assert_eq!(whole.values, grid.values);
+
+        // Windows which go past an edge
+        assert!(grid
+            .subgrid(Coord::new(3, 1), Dimensions::new(2, 2))
+            .is_none());
+        assert!(grid
+            .subgrid(Coord::new(1, 2), Dimensions::new(2, 2))
+            .is_none());
+        assert!(grid
+            .subgrid(Coord::new(-1, 0), Dimensions::new(2, 2))
+            .is_none());
+    }
+
+    #[test]
+    fn test_grid_map() {
+        let grid: Grid<u8> = Grid::from_input("0129\n9876", |c| c as u8 - b'0');
+
+        let peaks = grid.map(|_, height| *height == 9);
+        assert_eq!((peaks.dimensions.x, peaks.dimensions.y), (4, 2));
+        assert_eq!(
+            peaks.values,
+            vec![
+                vec![false, false, false, true],
+                vec![true, false, false, false]
+            ]
+        );
+
+        // Position dependent
+        let sums = grid.map(|coord, height| coord.x + coord.y + *height as i64);
+        assert_eq!(sums.get(&Coord::new(3, 1)), Some(10));
+    }
+
+    #[test]
+    fn test_grid_get_ref() {
+        // Doesn't need to be Clone
+        struct Cell(u8);
+
+        let grid = Grid {
+            dimensions: Dimensions::new(2, 2),
+            values: vec![vec![Cell(1), Cell(2)], vec![Cell(3), Cell(4)]],
+        };
+
+        assert_eq!(grid.get_ref(&Coord::new(1, 0)).map(|cell| cell.0), Some(2));
+        assert_eq!(grid.get_ref(&Coord::new(0, 1)).map(|cell| cell.0), Some(3));
+        assert!(grid.get_ref(&Coord::new(2, 0)).is_none());
+        assert!(grid.get_ref(&Coord::new(0, -1)).is_none());
+    }
+
+    #[test]
+    fn test_grid_get_mut_and_set() {
+        let mut grid = Grid::new(Dimensions::new(3, 2), vec![vec!['.'; 3]; 2]);
+
+        assert!(grid.set(&Coord::new(2, 1), '#'));
+        assert_eq!(grid.get(&Coord::new(2, 1)), Some('#'));
+        assert_eq!(grid.values[1], ['.', '.', '#']);
+
+        *grid.get_mut(&Coord::new(0, 0)).unwrap() = 'O';
+        assert_eq!(grid.get(&Coord::new(0, 0)), Some('O'));
+
+        // Out of bounds
+        assert!(!grid.set(&Coord::new(3, 0), '#'));
+        assert!(!grid.set(&Coord::new(0, -1), '#'));
+        assert!(grid.get_mut(&Coord::new(0, 2)).is_none());
+        assert_eq!(grid.positions_of(&'#').len(), 1);
+    }
+
+    #[test]
+    fn test_grid_iter() {
+        let grid = Grid::new(
+            Dimensions::new(3, 2),
+            vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']],
+        );
+
+        let cells: Vec<(Coord, &char)> = grid.iter().collect();
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[0], (Coord::new(0, 0), &'a'));
+        assert_eq!(cells[2], (Coord::new(2, 0), &'c'));
+        assert_eq!(cells[3], (Coord::new(0, 1), &'d'));
+        assert_eq!(cells[5], (Coord::new(2, 1), &'f'));
+
+        for (coord, val) in grid.iter() {
+            assert_eq!(grid.get(&coord).as_ref(), Some(val));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_grid_par_positions_of() {
+        let dimensions = Dimensions::new(101, 103);
+        let values = (0..dimensions.y)
+            .map(|y| (0..dimensions.x).map(|x| (x * 7 + y * 13) % 5).collect())
+            .collect();
+        let grid = Grid::new(dimensions, values);
+
+        for val in 0..5 {
+            assert_eq!(grid.par_positions_of(&val), grid.positions_of(&val));
+        }
+        assert!(grid.par_positions_of(&5).is_empty());
+    }
+
+    #[test]
+    fn test_grid_flood_fill() {
+        let grid = Grid::new(
+            Dimensions::new(4, 3),
+            vec![
+                vec!['A', 'A', 'B', 'A'],
+                vec!['B', 'A', 'B', 'B'],
+                vec!['A', 'B', 'A', 'A'],
+            ],
+        );
+
+        assert_eq!(
+            grid.flood_fill(&Coord::new(0, 0), false),
+            HashSet::from([Coord::new(0, 0), Coord::new(1, 0), Coord::new(1, 1)])
+        );
+
+        // isolated cell
+        assert_eq!(
+            grid.flood_fill(&Coord::new(0, 2), false),
+            HashSet::from([Coord::new(0, 2)])
+        );
+
+        // connected through diagonals
+        assert_eq!(
+            grid.flood_fill(&Coord::new(0, 2), true),
+            HashSet::from([
+                Coord::new(0, 2),
+                Coord::new(1, 1),
+                Coord::new(0, 0),
+                Coord::new(1, 0),
+                Coord::new(2, 2),
+                Coord::new(3, 2),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_grid_with_markers() {
+        let input = "\
+####
+#.@#
+#O.#
+####";
+        let (grid, markers) = parse_grid_with_markers(input, &['@', '#', 'S']);
+
+        assert_eq!(grid.dimensions.x, 4);
+        assert_eq!(grid.dimensions.y, 4);
+        assert_eq!(grid.get(&Coord::new(1, 2)), Some('O'));
+        assert_eq!(grid.render(|c| *c), input);
+
+        assert_eq!(markers[&'@'], [Coord::new(2, 1)]);
+        assert_eq!(markers[&'#'].len(), 12);
+        assert!(markers[&'#'].contains(&Coord::new(0, 0)));
+        assert!(markers[&'#'].contains(&Coord::new(3, 3)));
+        assert!(!markers[&'#'].contains(&Coord::new(1, 1)));
+        assert!(markers[&'S'].is_empty());
+        assert!(!markers.contains_key(&'O'));
+    }
+
+    #[test]
+    fn test_maze_builder() {
+        let input = "\
+####
+#.E#
+#S.#
+####";
+
+        let mut builder = MazeBuilder::with_dimensions(Dimensions::new(4, 4))
+            .start(Coord::new(1, 1))
+            .end(Coord::new(2, 2));
+        for x in 0..4 {
+            builder = builder.wall(Coord::new(x, 0)).wall(Coord::new(x, 3));
+        }
+        for y in 1..3 {
+            builder = builder.wall(Coord::new(0, y)).wall(Coord::new(3, y));
+        }
+
+        assert_eq!(builder.build(), parse_maze(input));
+    }
+
+    #[test]
+    fn test_parse_maze_with_dimensions() {
+        let input = "\
+#####
+#..E#
+#S..#
+#####";
+
+        let (maze, dimensions) = parse_maze_with_dimensions(input);
+        let expected_dimensions = Dimensions::from_input(input);
+        assert_eq!(
+            (dimensions.x, dimensions.y),
+            (expected_dimensions.x, expected_dimensions.y)
+        );
+        assert_eq!((dimensions.x, dimensions.y), (5, 4));
+
+        assert_eq!(maze, parse_maze(input));
+        assert_eq!(maze.start, Coord::new(1, 1));
+        assert_eq!(maze.end, Coord::new(3, 2));
+        assert_eq!(maze.walls.len(), 14);
+    }
+
+    #[test]
+    fn test_try_parse_maze() {
+        let maze = try_parse_maze("#S.E#").unwrap();
+        assert_eq!(maze, parse_maze("#S.E#"));
+
+        assert_eq!(
+            try_parse_maze("#..E#").err(),
+            Some("Did not find starting position S".to_string())
+        );
+        assert_eq!(
+            try_parse_maze("#S..#").err(),
+            Some("Did not find end position E".to_string())
+        );
+        assert_eq!(
+            try_parse_maze("#S.E#\n#..E#").err(),
+            Some("Found more than one end position E, at (3, 0) and (3, 1)".to_string())
+        );
+        assert!(try_parse_maze("").is_err());
+    }
+
+    #[test]
+    fn test_bfs_distances() {
+        let maze = parse_maze(
+            "\
+#######
+#S..#.#
+#.#...#
+#...#E#
+#######",
+        );
+        let distances = bfs_distances(maze.start, |pos| {
+            pos.get_neighbors()
+                .filter(|neighbor| !maze.walls.contains(neighbor))
+                .collect()
+        });
+
+        assert_eq!(distances[&maze.start], 0);
+        assert_eq!(distances[&Coord::new(3, 3)], 2);
+        assert_eq!(distances[&Coord::new(3, 1)], 4);
+        assert_eq!(distances[&Coord::new(5, 3)], 6);
+        assert_eq!(distances[&maze.end], 6);
+
+        // Every open cell is reachable, walls are not
+        assert_eq!(distances.len(), 12);
+        assert!(!distances.contains_key(&Coord::new(2, 2)));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        //   a --1-- b --1-- c
+        //   |               |
+        //   +-------5-------+-- 1 -- d
+        let edges: HashMap<char, Vec<(char, usize)>> = HashMap::from([
+            ('a', vec![('b', 1), ('c', 5)]),
+            ('b', vec![('a', 1), ('c', 1)]),
+            ('c', vec![('a', 5), ('b', 1), ('d', 1)]),
+            ('d', vec![('c', 1)]),
+            ('e', vec![]),
+        ]);
+        let successors = |node: &char| edges[node].clone();
+
+        assert_eq!(dijkstra('a', |node| *node == 'c', successors), Some(2));
+        assert_eq!(dijkstra('a', |node| *node == 'd', successors), Some(3));
+        assert_eq!(dijkstra('a', |node| *node == 'a', successors), Some(0));
+        assert_eq!(dijkstra('a', |node| *node == 'e', successors), None);
+
+        let costs = dijkstra_costs('a', successors);
+        assert_eq!(
+            costs,
+            HashMap::from([('a', 0), ('b', 1), ('c', 2), ('d', 3)])
+        );
+
+        // Stops at the nearest of several goals
+        assert_eq!(
+            dijkstra('d', |node| "ab".contains(*node), successors),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_all_paths() {
+        // Diamond with two equal cost paths from a to d, and a more expensive path via e
+        //     b
+        //   /   \
+        //  a     d
+        //   \   /
+        //     c
+        //  a --- e --- d
+        let edges: HashMap<char, Vec<(char, usize)>> = HashMap::from([
+            ('a', vec![('b', 1), ('c', 2), ('e', 1)]),
+            ('b', vec![('d', 2)]),
+            ('c', vec![('d', 1)]),
+            ('e', vec![('d', 5)]),
+            ('d', vec![]),
+            ('f', vec![]),
+        ]);
+        let successors = |node: &char| edges[node].clone();
+
+        let (cost, nodes) = dijkstra_all_paths('a', |node| *node == 'd', successors).unwrap();
+        assert_eq!(cost, 3);
+        assert_eq!(nodes, HashSet::from(['a', 'b', 'c', 'd']));
+        assert_eq!(dijkstra('a', |node| *node == 'd', successors), Some(cost));
+
+        // Start is the goal
+        assert_eq!(
+            dijkstra_all_paths('a', |node| *node == 'a', successors),
+            Some((0, HashSet::from(['a'])))
+        );
+
+        assert_eq!(
+            dijkstra_all_paths('a', |node| *node == 'f', successors),
+            None
+        );
+    }
+
+    #[test]
+    fn test_reduce_vector() {
+        assert_eq!(reduce_vector(4, 2), (2, 1));
+        assert_eq!(reduce_vector(-4, 6), (-2, 3));
+        assert_eq!(reduce_vector(-3, -3), (-1, -1));
+        assert_eq!(reduce_vector(0, -5), (0, -1));
+        assert_eq!(reduce_vector(3, 5), (3, 5));
+        assert_eq!(reduce_vector(0, 0), (0, 0));
+    }
+
+    #[test]
+    fn test_reconstruct_path() {
+        // a -> b -> c -> d, with a branch b -> e
+        let came_from = HashMap::from([('b', 'a'), ('c', 'b'), ('d', 'c'), ('e', 'b')]);
+
+        assert_eq!(reconstruct_path(&came_from, 'd'), ['a', 'b', 'c', 'd']);
+        assert_eq!(reconstruct_path(&came_from, 'e'), ['a', 'b', 'e']);
+        assert_eq!(reconstruct_path(&came_from, 'a'), ['a']);
+
+        // Works with coords as states too
+        let came_from = HashMap::from([
+            (Coord::new(1, 0), Coord::new(0, 0)),
+            (Coord::new(1, 1), Coord::new(1, 0)),
+        ]);
+        assert_eq!(
+            reconstruct_path(&came_from, Coord::new(1, 1)),
+            [Coord::new(0, 0), Coord::new(1, 0), Coord::new(1, 1)]
+        );
     }
 }
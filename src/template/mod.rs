@@ -36,6 +36,42 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     f.expect("could not open input file")
 }
 
+/// Helper function that reads a text file to string, appending a part suffix.
+/// Returns `None` instead of panicking if the file does not exist.
+#[must_use]
+pub fn try_read_file_part(folder: &str, day: Day, part: u8) -> Option<String> {
+    let cwd = env::current_dir().unwrap();
+    let filepath = cwd
+        .join("data")
+        .join(folder)
+        .join(format!("{day}-{part}.txt"));
+    fs::read_to_string(filepath).ok()
+}
+
+/// Helper function that lists the days with at least one file in the folder,
+/// either `01.txt` or with a part suffix like `01-2.txt`. Returns the days sorted.
+#[must_use]
+pub fn available_days(folder: &str) -> Vec<u32> {
+    let cwd = env::current_dir().unwrap();
+    let Ok(entries) = fs::read_dir(cwd.join("data").join(folder)) else {
+        return Vec::new();
+    };
+
+    let mut days: Vec<u32> = entries
+        .filter_map(|entry| {
+            let file_name = entry.ok()?.file_name().into_string().ok()?;
+            let stem = file_name.strip_suffix(".txt")?;
+            let day = stem.split_once('-').map_or(stem, |(day, _part)| day);
+            let day: Day = day.parse().ok()?;
+            Some(u32::from(day.into_inner()))
+        })
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+
+    days
+}
+
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
@@ -66,3 +102,46 @@ macro_rules! solution {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{available_days, try_read_file_part, Day};
+    use crate::day;
+    use std::fs;
+
+    #[test]
+    fn try_read_file_part_existing() {
+        assert!(try_read_file_part("examples", day!(22), 1).is_some());
+    }
+
+    #[test]
+    fn try_read_file_part_missing() {
+        assert_eq!(try_read_file_part("examples", day!(25), 2), None);
+    }
+
+    #[test]
+    fn available_days_examples() {
+        let days = available_days("examples");
+
+        // Sorted, with each day once even if it has several part files
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        // 03 only has part files, 25 is the last day
+        assert!(days.contains(&3));
+        assert!(days.contains(&25));
+
+        // Every day has an example file, either for the whole day or for part 1
+        for day in days {
+            let day = Day::new(day as u8).unwrap();
+            assert!(
+                fs::metadata(format!("data/examples/{day}.txt")).is_ok()
+                    || try_read_file_part("examples", day, 1).is_some(),
+                "No example file for day {day}"
+            );
+        }
+    }
+
+    #[test]
+    fn available_days_missing_folder() {
+        assert!(available_days("does-not-exist").is_empty());
+    }
+}